@@ -1,88 +1,154 @@
-use proc_macro2::{Ident, Span, TokenStream};
-use quote::quote;
+use proc_macro2::{Ident, Span, TokenStream, TokenTree};
+use quote::{quote, quote_spanned, ToTokens};
 use syn::{
-    parenthesized, parse::Parse, DeriveInput, Field, FieldsNamed, FieldsUnnamed, LitStr, Token,
+    parenthesized, parse::Parse, parse_macro_input, parse_quote, punctuated::Punctuated,
+    spanned::Spanned, Attribute, DeriveInput, Field, FieldsNamed, FieldsUnnamed, GenericParam,
+    Generics, Index, Lit, LitStr, Member, Meta, MetaNameValue, Path, Token, Type,
 };
 
 #[proc_macro_derive(LiveMod, attributes(livemod))]
 pub fn livemod_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    let ast: DeriveInput = syn::parse(input).unwrap();
+    let ast = parse_macro_input!(input as DeriveInput);
+    let container = match ContainerAttrs::from_attrs(&ast.attrs) {
+        Ok(container) => container,
+        Err(error) => return error.to_compile_error().into(),
+    };
+    let label = container.label.unwrap_or_else(|| ast.ident.to_string());
+    let field_rule = container.rename_all.unwrap_or(RenameRule::Sentence);
 
     match ast.data {
         syn::Data::Struct(st) => {
             let struct_name = ast.ident;
-            let (
-                FieldsDerive {
-                    idents,
-                    default_values: _,
-                    representations,
-                    get_named_values,
-                    get_selves,
-                },
-                named,
-            ) = match st.fields {
-                syn::Fields::Named(fields) => (derive_fields_named(fields), true),
-                syn::Fields::Unnamed(fields) => (derive_fields_unnamed(fields), false),
+            let FieldsDerive {
+                members,
+                idents,
+                default_values: _,
+                representations,
+                get_named_values,
+                get_selves,
+                accept_arms,
+                has_fields,
+                constructs: _,
+                static_representations: _,
+                represented_types,
+                defaulted_types: _,
+                errors,
+            } = match st.fields {
+                syn::Fields::Named(fields) => derive_fields_named(fields, field_rule),
+                syn::Fields::Unnamed(fields) => derive_fields_unnamed(fields),
                 syn::Fields::Unit => {
+                    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
                     let gen = quote! {
-                        compile_error!("Derive not supported on unit struct")
+                        #[automatically_derived]
+                        impl #impl_generics ::livemod::LiveMod for #struct_name #ty_generics #where_clause {
+                            fn repr_default(&self, target: ::livemod::ActionTarget) -> ::livemod::Namespaced<::livemod::Repr> {
+                                if target.is_this() {
+                                    ::livemod::Namespaced::basic_structure_repr(#label, &[])
+                                } else {
+                                    ::livemod::Namespaced::unknown_field_repr()
+                                }
+                            }
+
+                            fn accept(&mut self, target: ::livemod::ActionTarget, _value: ::livemod::Parameter<::livemod::Value>) -> ::std::result::Result<bool, ::livemod::AcceptError> {
+                                match target.strip_one_field() {
+                                    Some((name, _)) => ::std::result::Result::Err(::livemod::AcceptError::UnknownField(name.to_owned())),
+                                    None => ::std::result::Result::Err(::livemod::AcceptError::MissingField),
+                                }
+                            }
+
+                            fn get_self(&self, target: ::livemod::ActionTarget) -> ::livemod::Parameter<::livemod::Value> {
+                                if target.is_this() {
+                                    ::livemod::Parameter::Namespaced(::livemod::Namespaced::basic_structure_value(&[]))
+                                } else {
+                                    ::livemod::Namespaced::unknown_field_value()
+                                }
+                            }
+                        }
+
+                        #[automatically_derived]
+                        impl #impl_generics ::livemod::LiveModStruct for #struct_name #ty_generics #where_clause {
+                            fn has_field(_name: &str) -> bool {
+                                false
+                            }
+                        }
                     };
                     return gen.into();
                 }
             };
 
-            let self_pattern = if named {
-                quote! { Self { #(#idents),* } }
-            } else {
-                quote! { Self ( #(#idents),* ) }
-            };
+            let self_pattern = quote! { Self { #(#members: #idents),* } };
+            let run_on_change = run_on_change(&container.on_change);
+
+            let generics = add_trait_bounds(
+                ast.generics,
+                parse_quote!(::livemod::LiveMod),
+                &represented_types,
+                &[],
+            );
+            let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+            if !errors.is_empty() {
+                // Only report the errors, rather than any which follow from them in the generated code
+                return quote! { #(#errors)* }.into();
+            }
 
             let gen = quote! {
                 #[automatically_derived]
-                impl ::livemod::LiveMod for #struct_name {
+                impl #impl_generics ::livemod::LiveMod for #struct_name #ty_generics #where_clause {
                     fn repr_default(&self, target: ::livemod::ActionTarget) -> ::livemod::Namespaced<::livemod::Repr> {
                         let #self_pattern = self;
-                        if let Some((field, field_target)) = target.strip_one_field() {
+                        if let Some((name, field_target)) = target.strip_one_field() {
+                            let field = match name {
+                                #(#get_named_values,)*
+                                _ => ::std::option::Option::None,
+                            };
                             match field {
-                                #(#get_named_values as &dyn ::livemod::LiveMod,)*
-                                _ => panic!("Unexpected value name!"),
-                            }.repr_default(field_target)
+                                ::std::option::Option::Some((field, field_target)) => field.repr_default(field_target),
+                                ::std::option::Option::None => ::livemod::Namespaced::unknown_field_repr(),
+                            }
                         } else {
-                            ::livemod::Namespaced::basic_structure_repr(
-                                stringify!(#struct_name),
-                                &[
-                                    #(#representations),*
-                                ],
-                            )
+                            ::livemod::Namespaced::basic_structure_repr(#label, &#representations)
                         }
                     }
 
-                    fn accept(&mut self, target: ::livemod::ActionTarget, value: ::livemod::Parameter<::livemod::Value>) -> bool {
-                        let #self_pattern = self;
-                        if let Some((field, field_target)) = target.strip_one_field() {
-                            match field {
-                                #(#get_named_values as &mut dyn ::livemod::LiveMod,)*
-                                _ => panic!("Unexpected target!"),
-                            }.accept(field_target, value)
-                        } else {
-                            panic!("Unexpected value!")
-                        }
+                    fn accept(&mut self, target: ::livemod::ActionTarget, value: ::livemod::Parameter<::livemod::Value>) -> ::std::result::Result<bool, ::livemod::AcceptError> {
+                        let (livemod_changed, livemod_on_change): (bool, ::std::option::Option<fn(&mut Self)>) = {
+                            let #self_pattern = &mut *self;
+                            if let Some((name, field_target)) = target.strip_one_field() {
+                                match name {
+                                    #(#accept_arms,)*
+                                    _ => return ::std::result::Result::Err(::livemod::AcceptError::UnknownField(name.to_owned())),
+                                }
+                            } else {
+                                return ::std::result::Result::Err(::livemod::AcceptError::MissingField)
+                            }
+                        };
+                        #run_on_change
                     }
 
                     fn get_self(&self, target: ::livemod::ActionTarget) -> ::livemod::Parameter<::livemod::Value> {
                         let #self_pattern = self;
-                        if let Some((field, field_target)) = target.strip_one_field() {
+                        if let Some((name, field_target)) = target.strip_one_field() {
+                            let field = match name {
+                                #(#get_named_values,)*
+                                _ => ::std::option::Option::None,
+                            };
                             match field {
-                                #(#get_named_values as &dyn ::livemod::LiveMod,)*
-                                _ => panic!("Unexpected value name!"),
-                            }.get_self(field_target)
+                                ::std::option::Option::Some((field, field_target)) => field.get_self(field_target),
+                                ::std::option::Option::None => ::livemod::Namespaced::unknown_field_value(),
+                            }
                         } else {
-                            ::livemod::Parameter::Namespaced(::livemod::Namespaced::basic_structure_value(&[
-                                #(#get_selves),*
-                            ]))
+                            ::livemod::Parameter::Namespaced(::livemod::Namespaced::basic_structure_value(&#get_selves))
                         }
                     }
                 }
+
+                #[automatically_derived]
+                impl #impl_generics ::livemod::LiveModStruct for #struct_name #ty_generics #where_clause {
+                    fn has_field(name: &str) -> bool {
+                        false #(|| #has_fields)*
+                    }
+                }
             };
             gen.into()
         }
@@ -92,157 +158,267 @@ pub fn livemod_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream
             let mut variant_names = vec![];
             let mut variant_fields = vec![];
             let mut variant_get_named_values = vec![];
-            let mut variant_get_named_values_mut = vec![];
+            let mut variant_accepts = vec![];
             let mut variant_defaults = vec![];
+            let mut variant_selected = vec![];
             let mut variant_get_selves = vec![];
+            let mut represented_types = vec![];
+            let mut defaulted_types = vec![];
+            let mut errors = vec![];
+
+            // Enums without any fields are displayed as a single dropdown, without the `current` fields
+            let fieldless = en
+                .variants
+                .iter()
+                .all(|variant| matches!(variant.fields, syn::Fields::Unit));
 
             for variant in en.variants {
                 let variant_name = variant.ident;
-                let variant_string = variant_name.to_string();
+                let (variant_string, variant_default) = variant_attrs(
+                    &variant_name,
+                    variant.attrs,
+                    container.rename_all,
+                    &mut errors,
+                );
                 variant_names.push(variant_string.clone());
                 match variant.fields {
                     syn::Fields::Named(fields) => {
                         let FieldsDerive {
+                            members,
                             idents,
                             default_values,
                             representations,
                             get_named_values,
                             get_selves,
-                        } = derive_fields_named(fields);
+                            accept_arms,
+                            has_fields: _,
+                            constructs: _,
+                            static_representations: _,
+                            represented_types: variant_represented_types,
+                            defaulted_types: variant_defaulted_types,
+                            errors: variant_errors,
+                        } = derive_fields_named(fields, field_rule);
+                        represented_types.extend(variant_represented_types);
+                        defaulted_types.extend(variant_defaulted_types);
+                        errors.extend(variant_errors);
                         let self_pattern = quote! {
-                            Self::#variant_name { #(#idents),* }
+                            Self::#variant_name { #(#members: #idents),* }
                         };
 
-                        variant_fields
-                            .push(quote! { #self_pattern => vec![#(#representations),*] });
-                        variant_get_named_values.push(quote! { #self_pattern => match name { #(#get_named_values as &dyn ::livemod::LiveMod,)* _ => panic!("Unexpected value name!") } });
-                        variant_get_named_values_mut.push(quote! { #self_pattern => match name { #(#get_named_values as &mut dyn ::livemod::LiveMod,)* _ => panic!("Unexpected value name!") } });
-                        variant_defaults.push(quote! { #variant_string => Self::#variant_name { #(#idents: #default_values),* } });
+                        variant_fields.push(quote! { #self_pattern => #representations });
+                        variant_get_named_values.push(quote! { #self_pattern => match name { #(#get_named_values,)* _ => ::std::option::Option::None } });
+                        variant_accepts.push(quote! { #self_pattern => match name { #(#accept_arms,)* _ => return ::std::result::Result::Err(::livemod::AcceptError::UnknownField(name.to_owned())) } });
+                        let variant_default = variant_default.unwrap_or_else(|| {
+                            quote! { Self::#variant_name { #(#members: #default_values),* } }
+                        });
+                        variant_defaults.push(quote! { #variant_string => #variant_default });
+                        variant_selected
+                            .push(quote! { Self::#variant_name { .. } => #variant_string });
                         variant_get_selves.push(quote! {
                             #self_pattern => ::livemod::Namespaced::new(
                                 vec![String::from("livemod"), String::from("enum")],
-                                <_ as ::std::iter::FromIterator<_>>::from_iter(::std::array::IntoIter::new([
-                                    (String::from("variant"), Parameter::String(String::from(#variant_string))),
-                                    (String::from("current"), Parameter::Namespaced(::livemod::Namespaced::fields_value(&[#(#get_selves),*]))),
+                                <_ as ::std::iter::FromIterator<_>>::from_iter(::std::iter::IntoIterator::into_iter([
+                                    (String::from("variant"), ::livemod::Parameter::String(String::from(#variant_string))),
+                                    (String::from("current"), ::livemod::Parameter::Namespaced(::livemod::Namespaced::fields_value(&#get_selves))),
                                 ]))
                             )
                         });
                     }
                     syn::Fields::Unnamed(fields) => {
                         let FieldsDerive {
+                            members,
                             idents,
                             default_values,
                             representations,
                             get_named_values,
                             get_selves,
+                            accept_arms,
+                            has_fields: _,
+                            constructs: _,
+                            static_representations: _,
+                            represented_types: variant_represented_types,
+                            defaulted_types: variant_defaulted_types,
+                            errors: variant_errors,
                         } = derive_fields_unnamed(fields);
+                        represented_types.extend(variant_represented_types);
+                        defaulted_types.extend(variant_defaulted_types);
+                        errors.extend(variant_errors);
                         let self_pattern = quote! {
-                            Self::#variant_name ( #(#idents),* )
+                            Self::#variant_name { #(#members: #idents),* }
                         };
 
-                        variant_fields
-                            .push(quote! { #self_pattern => vec![#(#representations),*] });
-                        variant_get_named_values.push(quote! { #self_pattern => match name { #(#get_named_values as &dyn ::livemod::LiveMod,)* _ => panic!("Unexpected value name!") } });
-                        variant_get_named_values_mut.push(quote! { #self_pattern => match name { #(#get_named_values as &mut dyn ::livemod::LiveMod,)* _ => panic!("Unexpected value name!") } });
-                        variant_defaults.push(quote! { #variant_string => Self::#variant_name ( #(#default_values),* ) });
+                        variant_fields.push(quote! { #self_pattern => #representations });
+                        variant_get_named_values.push(quote! { #self_pattern => match name { #(#get_named_values,)* _ => ::std::option::Option::None } });
+                        variant_accepts.push(quote! { #self_pattern => match name { #(#accept_arms,)* _ => return ::std::result::Result::Err(::livemod::AcceptError::UnknownField(name.to_owned())) } });
+                        let variant_default = variant_default.unwrap_or_else(|| {
+                            quote! { Self::#variant_name { #(#members: #default_values),* } }
+                        });
+                        variant_defaults.push(quote! { #variant_string => #variant_default });
+                        variant_selected
+                            .push(quote! { Self::#variant_name { .. } => #variant_string });
                         variant_get_selves.push(quote! {
                             #self_pattern => ::livemod::Namespaced::new(
                                 vec![String::from("livemod"), String::from("enum")],
-                                <_ as ::std::iter::FromIterator<_>>::from_iter(::std::array::IntoIter::new([
-                                    (String::from("variant"), Parameter::String(String::from(#variant_string))),
-                                    (String::from("current"), Parameter::Namespaced(::livemod::Namespaced::fields_value(&[#(#get_selves),*]))),
+                                <_ as ::std::iter::FromIterator<_>>::from_iter(::std::iter::IntoIterator::into_iter([
+                                    (String::from("variant"), ::livemod::Parameter::String(String::from(#variant_string))),
+                                    (String::from("current"), ::livemod::Parameter::Namespaced(::livemod::Namespaced::fields_value(&#get_selves))),
                                 ]))
                             )
                         });
                     }
                     syn::Fields::Unit => {
                         variant_fields.push(quote! { Self::#variant_name => vec![] });
-                        variant_get_named_values.push(
-                            quote! { Self::#variant_name => panic!("Unexpected value name!") },
-                        );
-                        variant_get_named_values_mut.push(
-                            quote! { Self::#variant_name => panic!("Unexpected value name!") },
+                        variant_get_named_values
+                            .push(quote! { Self::#variant_name => ::std::option::Option::None });
+                        variant_accepts.push(
+                            quote! { Self::#variant_name => return ::std::result::Result::Err(::livemod::AcceptError::UnknownField(name.to_owned())) },
                         );
-                        variant_defaults.push(quote! { #variant_string => Self::#variant_name });
-                        variant_get_selves.push(quote! { Self::#variant_name => ::livemod::Namespaced::new(vec![String::from("livemod"), String::from("enum")], <_ as ::std::iter::FromIterator<_>>::from_iter(::std::array::IntoIter::new([(String::from("variant"), Parameter::String(String::from(#variant_string)))]))) });
+                        let variant_default =
+                            variant_default.unwrap_or_else(|| quote! { Self::#variant_name });
+                        variant_defaults.push(quote! { #variant_string => #variant_default });
+                        variant_selected
+                            .push(quote! { Self::#variant_name { .. } => #variant_string });
+                        variant_get_selves.push(quote! { Self::#variant_name => ::livemod::Namespaced::new(vec![String::from("livemod"), String::from("enum")], <_ as ::std::iter::FromIterator<_>>::from_iter(::std::iter::IntoIterator::into_iter([(String::from("variant"), ::livemod::Parameter::String(String::from(#variant_string)))]))) });
                     }
                 }
             }
 
+            let generics = add_trait_bounds(
+                ast.generics,
+                parse_quote!(::livemod::LiveMod),
+                &represented_types,
+                &defaulted_types,
+            );
+            let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+            let current_repr = if fieldless {
+                quote! {}
+            } else {
+                quote! {
+                    (
+                        String::from("current"),
+                        ::livemod::Parameter::Namespaced(::livemod::Namespaced::fields_repr(
+                            //FIXME: If anybody can help me with the unneccesary heap allocation in here, please do so. I'm sick of macros.
+                            &match self {
+                                #(#variant_fields ,)*
+                            }.into_iter().map(|(s, n)| (s, ::livemod::Parameter::Namespaced(n))).collect::<::std::vec::Vec<_>>()
+                        )),
+                    ),
+                }
+            };
+
+            // A fieldless enum has no fields to look up, and every arm of the lookup `match` would diverge
+            let (field_repr_default, field_accept, field_get_self) = if fieldless {
+                (
+                    quote! { ::livemod::Namespaced::unknown_field_repr() },
+                    quote! { return ::std::result::Result::Err(::livemod::AcceptError::UnknownField(name.to_owned())) },
+                    quote! { ::livemod::Namespaced::unknown_field_value() },
+                )
+            } else {
+                (
+                    quote! {
+                        let field = match self {
+                            #(#variant_get_named_values ,)*
+                        };
+                        match field {
+                            ::std::option::Option::Some((field, field_target)) => field.repr_default(field_target),
+                            ::std::option::Option::None => ::livemod::Namespaced::unknown_field_repr(),
+                        }
+                    },
+                    quote! {
+                        let target = field_target;
+                        if let Some((name, field_target)) = target.strip_one_field() {
+                            match self {
+                                #(#variant_accepts ,)*
+                            }
+                        } else {
+                            return ::std::result::Result::Err(::livemod::AcceptError::MissingField)
+                        }
+                    },
+                    quote! {
+                        let field = match self {
+                            #(#variant_get_named_values ,)*
+                        };
+                        match field {
+                            ::std::option::Option::Some((field, field_target)) => field.get_self(field_target),
+                            ::std::option::Option::None => ::livemod::Namespaced::unknown_field_value(),
+                        }
+                    },
+                )
+            };
+            let field_binding = if fieldless {
+                quote! { (_, _) }
+            } else {
+                quote! { (name, field_target) }
+            };
+            let run_on_change = run_on_change(&container.on_change);
+
+            if !errors.is_empty() {
+                return quote! { #(#errors)* }.into();
+            }
+
             let gen = quote! {
                 #[automatically_derived]
-                impl ::livemod::LiveMod for #enum_name {
+                impl #impl_generics ::livemod::LiveMod for #enum_name #ty_generics #where_clause {
                     fn repr_default(&self, target: ::livemod::ActionTarget) -> ::livemod::Namespaced<::livemod::Repr> {
-                        if let Some((name, field_target)) = target.strip_one_field() {
-                            match self {
-                                #(#variant_get_named_values ,)*
-                            }.repr_default(field_target)
+                        if let Some(#field_binding) = target.strip_one_field() {
+                            #field_repr_default
                         } else {
                             ::livemod::Namespaced::new(
                                 vec![
                                     String::from("livemod"),
                                     String::from("enum"),
                                 ],
-                                <_ as ::std::iter::FromIterator<_>>::from_iter(::std::array::IntoIter::new([
-                                    (String::from("name"), Parameter::String(String::from(stringify!(#enum_name)))),
+                                <_ as ::std::iter::FromIterator<_>>::from_iter(::std::iter::IntoIterator::into_iter([
+                                    (String::from("name"), ::livemod::Parameter::String(String::from(#label))),
                                     (
                                         String::from("variants"),
-                                        Parameter::Namespaced(Namespaced::new(
+                                        ::livemod::Parameter::Namespaced(::livemod::Namespaced::new(
                                             vec![String::from("livemod"), String::from("variants")],
                                             <_ as ::std::iter::FromIterator<_>>::from_iter(
-                                                ::std::array::IntoIter::new([
+                                                ::std::iter::IntoIterator::into_iter([
                                                     #(#variant_names),*
                                                 ])
                                                 .enumerate()
                                                 .map(|(i, variant_name)| {
-                                                    (i.to_string(), Parameter::String(variant_name.to_string()))
+                                                    (i.to_string(), ::livemod::Parameter::String(variant_name.to_string()))
                                                 })
                                             ),
                                         )),
                                     ),
-                                    (
-                                        String::from("current"),
-                                        Parameter::Namespaced(Namespaced::new(
-                                            vec![String::from("livemod"), String::from("fields")],
-                                            //FIXME: If anybody can help me with the unneccesary heap allocation in here, please do so. I'm sick of macros.
-                                            <_ as ::std::iter::FromIterator<_>>::from_iter(match self {
-                                                #(#variant_fields ,)*
-                                            }.into_iter().map(|(s, n)| (s, ::livemod::Parameter::Namespaced(n))))
-                                        )),
-                                    )
+                                    #current_repr
                                 ]))
                             )
                         }
                     }
 
-                    fn accept(&mut self, target: ::livemod::ActionTarget, value: ::livemod::Parameter<::livemod::Value>) -> bool {
-                        if let Some((name, field_target)) = target.strip_one_field() {
+                    fn accept(&mut self, target: ::livemod::ActionTarget, value: ::livemod::Parameter<::livemod::Value>) -> ::std::result::Result<bool, ::livemod::AcceptError> {
+                        let (livemod_changed, livemod_on_change): (bool, ::std::option::Option<fn(&mut Self)>) = if let Some((name, field_target)) = target.strip_one_field() {
                             if name == "variant" {
-                                let variant_name = value.as_string().unwrap();
-                                *self = match variant_name.as_str() {
-                                    #(#variant_defaults ,)*
-                                    name => panic!("Unknown variant name: {}", name)
+                                let variant_name = value.as_string().ok_or(::livemod::AcceptError::WrongType { expected: "a variant name" })?;
+                                let selected = match *self {
+                                    #(#variant_selected ,)*
                                 };
-                                true
-                            } else {
-                                if let Some((name, field_target)) = field_target.strip_one_field() {
-                                    match self {
-                                        #(#variant_get_named_values_mut ,)*
-                                    }.accept(field_target, value)
-                                } else {
-                                    unimplemented!()
+                                // Selecting the current variant again keeps the values of its fields, and only runs the hooks
+                                if variant_name != selected {
+                                    *self = match variant_name.as_str() {
+                                        #(#variant_defaults ,)*
+                                        name => return ::std::result::Result::Err(::livemod::AcceptError::UnknownVariant(name.to_owned())),
+                                    };
                                 }
+                                (true, None)
+                            } else {
+                                #field_accept
                             }
                         } else {
-                            unimplemented!()
-                        }
+                            return ::std::result::Result::Err(::livemod::AcceptError::MissingField)
+                        };
+                        #run_on_change
                     }
 
-                    fn get_self(&self, target: ActionTarget) -> ::livemod::Parameter<::livemod::Value> {
-                        if let Some((name, field_target)) = target.strip_one_field() {
-                            match self {
-                                #(#variant_get_named_values ,)*
-                            }.get_self(field_target)
+                    fn get_self(&self, target: ::livemod::ActionTarget) -> ::livemod::Parameter<::livemod::Value> {
+                        if let Some(#field_binding) = target.strip_one_field() {
+                            #field_get_self
                         } else {
                             ::livemod::Parameter::Namespaced(match self {
                                 #(#variant_get_selves ,)*
@@ -253,115 +429,411 @@ pub fn livemod_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream
             };
             gen.into()
         }
-        syn::Data::Union(_) => {
+        syn::Data::Union(un) => {
+            syn::Error::new_spanned(un.union_token, "LiveMod can't be derived for unions")
+                .to_compile_error()
+                .into()
+        }
+    }
+}
+
+#[proc_macro_derive(LiveModCtor, attributes(livemod))]
+pub fn livemod_ctor_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    let container = match ContainerAttrs::from_attrs(&ast.attrs) {
+        Ok(container) => container,
+        Err(error) => return error.to_compile_error().into(),
+    };
+    let label = container.label.unwrap_or_else(|| ast.ident.to_string());
+    let field_rule = container.rename_all.unwrap_or(RenameRule::Sentence);
+
+    match ast.data {
+        syn::Data::Struct(st) => {
+            let struct_name = ast.ident;
+            let FieldsDerive {
+                members,
+                constructs,
+                static_representations,
+                represented_types,
+                defaulted_types,
+                errors,
+                ..
+            } = match st.fields {
+                syn::Fields::Named(fields) => derive_fields_named(fields, field_rule),
+                syn::Fields::Unnamed(fields) => derive_fields_unnamed(fields),
+                syn::Fields::Unit => derive_fields_unnamed(FieldsUnnamed {
+                    paren_token: Default::default(),
+                    unnamed: Default::default(),
+                }),
+            };
+
+            let generics = add_trait_bounds(
+                ast.generics,
+                parse_quote!(::livemod::LiveModCtor),
+                &represented_types,
+                &defaulted_types,
+            );
+            let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+            if !errors.is_empty() {
+                return quote! { #(#errors)* }.into();
+            }
+
             let gen = quote! {
-                compile_error!("Derive not supported on union")
+                #[automatically_derived]
+                impl #impl_generics ::livemod::LiveModCtor for #struct_name #ty_generics #where_clause {
+                    fn repr_static() -> ::livemod::Namespaced<::livemod::Repr> {
+                        ::livemod::Namespaced::basic_structure_repr(#label, &#static_representations)
+                    }
+
+                    #[allow(unused_mut)]
+                    fn from_value(value: ::livemod::Parameter<::livemod::Value>) -> Option<Self> {
+                        let mut value = value.try_into_namespaced().ok()?;
+                        if value.name != ["livemod", "struct"] {
+                            return None;
+                        }
+                        Some(Self { #(#members: #constructs),* })
+                    }
+                }
+            };
+            gen.into()
+        }
+        syn::Data::Enum(en) => {
+            let enum_name = ast.ident;
+
+            let mut variant_names = vec![];
+            let mut variant_idents = vec![];
+            let mut errors = vec![];
+
+            for variant in en.variants {
+                let variant_name = variant.ident;
+                let (variant_string, default) = variant_attrs(
+                    &variant_name,
+                    variant.attrs,
+                    container.rename_all,
+                    &mut errors,
+                );
+                // Only unit variants are supported, so a default value would be ignored
+                if let Some(default) = default {
+                    errors.push(
+                        syn::Error::new_spanned(
+                            default,
+                            "`default` can't be used on the variants of enums deriving LiveModCtor",
+                        )
+                        .to_compile_error(),
+                    );
+                }
+                if !matches!(variant.fields, syn::Fields::Unit) {
+                    // The fields of the selected variant depend on the value, so they can't be part of `repr_static`
+                    errors.push(
+                        syn::Error::new_spanned(
+                            &variant_name,
+                            "LiveModCtor can only be derived for enums without fields",
+                        )
+                        .to_compile_error(),
+                    );
+                }
+                variant_names.push(variant_string);
+                variant_idents.push(variant_name);
+            }
+
+            let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+
+            if !errors.is_empty() {
+                return quote! { #(#errors)* }.into();
+            }
+
+            let gen = quote! {
+                #[automatically_derived]
+                impl #impl_generics ::livemod::LiveModCtor for #enum_name #ty_generics #where_clause {
+                    fn repr_static() -> ::livemod::Namespaced<::livemod::Repr> {
+                        ::livemod::Namespaced::new(
+                            vec![
+                                String::from("livemod"),
+                                String::from("enum"),
+                            ],
+                            <_ as ::std::iter::FromIterator<_>>::from_iter(::std::iter::IntoIterator::into_iter([
+                                (String::from("name"), ::livemod::Parameter::String(String::from(#label))),
+                                (
+                                    String::from("variants"),
+                                    ::livemod::Parameter::Namespaced(::livemod::Namespaced::new(
+                                        vec![String::from("livemod"), String::from("variants")],
+                                        <_ as ::std::iter::FromIterator<_>>::from_iter(
+                                            ::std::iter::IntoIterator::into_iter([
+                                                #(#variant_names),*
+                                            ])
+                                            .enumerate()
+                                            .map(|(i, variant_name)| {
+                                                (i.to_string(), ::livemod::Parameter::String(variant_name.to_string()))
+                                            })
+                                        ),
+                                    )),
+                                ),
+                            ]))
+                        )
+                    }
+
+                    fn from_value(value: ::livemod::Parameter<::livemod::Value>) -> Option<Self> {
+                        let value = value.try_into_namespaced().ok()?;
+                        if value.name != ["livemod", "enum"] {
+                            return None;
+                        }
+                        match value.parameters.get("variant")?.as_string()?.as_str() {
+                            #(#variant_names => Some(Self::#variant_idents),)*
+                            _ => None,
+                        }
+                    }
+                }
             };
             gen.into()
         }
+        syn::Data::Union(un) => {
+            syn::Error::new_spanned(un.union_token, "LiveModCtor can't be derived for unions")
+                .to_compile_error()
+                .into()
+        }
     }
 }
 
 struct FieldsDerive {
+    members: Vec<Member>,
     idents: Vec<Ident>,
     default_values: Vec<TokenStream>,
-    representations: Vec<TokenStream>,
+    representations: TokenStream,
     get_named_values: Vec<TokenStream>,
-    get_selves: Vec<TokenStream>,
+    get_selves: TokenStream,
+    accept_arms: Vec<TokenStream>,
+    /// Expressions testing whether `name` is the name of each field
+    has_fields: Vec<TokenStream>,
+    constructs: Vec<TokenStream>,
+    static_representations: TokenStream,
+    represented_types: Vec<Type>,
+    defaulted_types: Vec<Type>,
+    /// Compile errors for invalid field attributes
+    errors: Vec<TokenStream>,
 }
 
 struct FieldDerive {
+    member: Member,
     ident: Ident,
     default_value: TokenStream,
     representation: Option<TokenStream>,
     get_named_value: Option<TokenStream>,
     get_self: Option<TokenStream>,
+    /// Match arm accepting a value for the field, given `name`, `field_target` and `value`
+    accept_arm: Option<TokenStream>,
+    /// Expression testing whether `name` is the name of the field, or of one of its fields if it is flattened
+    has_field: Option<TokenStream>,
+    /// Expression constructing the field from the `value` of a `LiveModCtor::from_value` call
+    construct: TokenStream,
+    static_representation: Option<TokenStream>,
+    /// The group the field is displayed in, if any
+    group: Option<String>,
+    /// The type of the field, if it is represented
+    represented_type: Option<Type>,
+    /// The type of the field, if it is constructed with `Default::default()`
+    defaulted_type: Option<Type>,
+    errors: Vec<TokenStream>,
 }
 
-fn derive_fields_named(fields: FieldsNamed) -> FieldsDerive {
-    let iter = fields.named.into_iter().map(|field| {
-        let ident = field.ident.clone().unwrap();
-        let name = ident.to_string();
-        derive_field(ident, name, field)
-    });
-
-    let mut gen = FieldsDerive {
-        idents: Vec::new(),
-        default_values: Vec::new(),
-        representations: Vec::new(),
-        get_named_values: Vec::new(),
-        get_selves: Vec::new(),
-    };
-
-    for field in iter {
-        gen.idents.push(field.ident);
-        gen.default_values.push(field.default_value);
-        gen.representations.extend(field.representation);
-        gen.get_named_values.extend(field.get_named_value);
-        gen.get_selves.extend(field.get_self);
-    }
-
-    gen
+fn derive_fields_named(fields: FieldsNamed, rule: RenameRule) -> FieldsDerive {
+    let labels = fields
+        .named
+        .iter()
+        .map(|field| {
+            let name = field.ident.as_ref().unwrap().to_string();
+            let name = name.trim_start_matches("r#").to_owned();
+            let label = field_rename(&field.attrs).unwrap_or_else(|| rule.apply(&name));
+            (name, label, field_skipped(&field.attrs))
+        })
+        .collect::<Vec<_>>();
+    collect_fields(
+        fields
+            .named
+            .into_iter()
+            .zip(&labels)
+            .map(|(field, (name, label, _))| {
+                let member = field.ident.clone().unwrap();
+                // Bind the field to a different name, so it can't shadow any variables in the generated code
+                let ident = Ident::new(&format!("__{}", name), Span::call_site());
+                derive_field(Member::Named(member), ident, label.clone(), field, &labels)
+            }),
+    )
 }
 
 fn derive_fields_unnamed(fields: FieldsUnnamed) -> FieldsDerive {
-    let iter = fields.unnamed.into_iter().enumerate().map(|(i, field)| {
+    let labels = fields
+        .unnamed
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let name = i.to_string();
+            let label = field_rename(&field.attrs).unwrap_or_else(|| name.clone());
+            (name, label, field_skipped(&field.attrs))
+        })
+        .collect::<Vec<_>>();
+    collect_fields(fields.unnamed.into_iter().enumerate().map(|(i, field)| {
         let ident = Ident::new(&format!("__{}", i), Span::call_site());
         let name = i.to_string();
-        derive_field(ident, name, field)
-    });
+        derive_field(Member::Unnamed(Index::from(i)), ident, name, field, &labels)
+    }))
+}
 
-    let mut gen = FieldsDerive {
-        idents: Vec::new(),
-        default_values: Vec::new(),
-        representations: Vec::new(),
-        get_named_values: Vec::new(),
-        get_selves: Vec::new(),
-    };
+/// Find the name given to a field with `#[livemod(rename)]`, if any. Invalid attributes are reported by
+/// [`derive_field`].
+fn field_rename(attrs: &[Attribute]) -> Option<String> {
+    parse_attrs(attrs, AttrTarget::Field)
+        .ok()?
+        .into_iter()
+        .find_map(|attr| match attr {
+            Attr::Rename(name) => Some(name),
+            _ => None,
+        })
+}
+
+/// Whether a field is skipped with `#[livemod(skip)]`.
+fn field_skipped(attrs: &[Attribute]) -> bool {
+    parse_attrs(attrs, AttrTarget::Field)
+        .map(|attrs| attrs.iter().any(|attr| matches!(attr, Attr::Skip)))
+        .unwrap_or(false)
+}
+
+fn collect_fields(iter: impl Iterator<Item = FieldDerive>) -> FieldsDerive {
+    let mut members = Vec::new();
+    let mut idents = Vec::new();
+    let mut default_values = Vec::new();
+    let mut representations = Vec::new();
+    let mut get_named_values = Vec::new();
+    let mut get_selves = Vec::new();
+    let mut accept_arms = Vec::new();
+    let mut has_fields = Vec::new();
+    let mut constructs = Vec::new();
+    let mut static_representations = Vec::new();
+    let mut represented_types = Vec::new();
+    let mut defaulted_types = Vec::new();
+    let mut errors = Vec::new();
+    // Name, member representations and member static representations of each group, along with the index of the
+    // group's placeholder in `representations` and `static_representations`
+    let mut groups: Vec<(String, Vec<TokenStream>, Vec<TokenStream>, usize)> = Vec::new();
 
     for field in iter {
-        gen.idents.push(field.ident);
-        gen.default_values.push(field.default_value);
-        gen.representations.extend(field.representation);
-        gen.get_named_values.extend(field.get_named_value);
-        gen.get_selves.extend(field.get_self);
+        members.push(field.member);
+        idents.push(field.ident);
+        default_values.push(field.default_value);
+        match (
+            field.group,
+            field.representation,
+            field.static_representation,
+        ) {
+            (Some(group), Some(representation), Some(static_representation)) => {
+                let index = match groups.iter().position(|(name, ..)| *name == group) {
+                    Some(index) => index,
+                    None => {
+                        groups.push((group, Vec::new(), Vec::new(), representations.len()));
+                        representations.push(TokenStream::new());
+                        static_representations.push(TokenStream::new());
+                        groups.len() - 1
+                    }
+                };
+                groups[index].1.push(representation);
+                groups[index].2.push(static_representation);
+            }
+            (_, representation, static_representation) => {
+                representations.extend(representation);
+                static_representations.extend(static_representation);
+            }
+        }
+        get_named_values.extend(field.get_named_value);
+        get_selves.extend(field.get_self);
+        accept_arms.extend(field.accept_arm);
+        has_fields.extend(field.has_field);
+        constructs.push(field.construct);
+        represented_types.extend(field.represented_type);
+        defaulted_types.extend(field.defaulted_type);
+        errors.extend(field.errors);
     }
 
-    gen
+    for (group, group_representations, group_static_representations, index) in groups {
+        let group_representations = collect_vec(&group_representations);
+        let group_static_representations = collect_vec(&group_static_representations);
+        representations[index] = quote! {
+            ::std::iter::once((#group.to_owned(), ::livemod::Namespaced::group_repr(#group, &#group_representations)))
+        };
+        static_representations[index] = quote! {
+            ::std::iter::once((#group.to_owned(), ::livemod::Namespaced::group_repr(#group, &#group_static_representations)))
+        };
+    }
+
+    FieldsDerive {
+        members,
+        idents,
+        default_values,
+        representations: collect_vec(&representations),
+        get_named_values,
+        get_selves: collect_vec(&get_selves),
+        accept_arms,
+        has_fields,
+        constructs,
+        static_representations: collect_vec(&static_representations),
+        represented_types,
+        defaulted_types,
+        errors,
+    }
 }
 
-fn derive_field(ident: Ident, default_name: String, field: Field) -> FieldDerive {
-    let attrs = match field
-        .attrs
-        .into_iter()
-        .filter_map(|attr| {
-            if attr.path.is_ident("livemod") {
-                Some(syn::parse2(attr.tokens))
-            } else {
-                None
-            }
-        })
-        .collect::<Result<Vec<_>, _>>()
-    {
+/// Chain the given iterators, and collect them into a `Vec`.
+fn collect_vec(iters: &[TokenStream]) -> TokenStream {
+    quote! {
+        ::std::iter::empty()#(.chain(#iters))*.collect::<::std::vec::Vec<_>>()
+    }
+}
+
+/// Derive the code for a single field. `labels` holds the name and label of each field of the containing struct, and
+/// whether it is skipped, so that conditions on sibling fields can refer to them.
+fn derive_field(
+    member: Member,
+    ident: Ident,
+    default_name: String,
+    field: Field,
+    labels: &[(String, String, bool)],
+) -> FieldDerive {
+    let ty = field.ty;
+    let doc_comment = doc_comment(&field.attrs);
+    let attrs = match parse_attrs(&field.attrs, AttrTarget::Field) {
         Ok(attrs) => attrs,
         Err(error) => {
+            // Treat the field as skipped, so the only errors reported are the ones in its attributes
             return FieldDerive {
+                member,
                 ident,
-                default_value: error.to_compile_error(),
+                default_value: quote! { ::std::unimplemented!() },
                 representation: None,
                 get_named_value: None,
                 get_self: None,
+                accept_arm: None,
+                has_field: None,
+                construct: quote! { ::std::unimplemented!() },
+                static_representation: None,
+                group: None,
+                represented_type: None,
+                defaulted_type: None,
+                errors: vec![error.to_compile_error()],
             };
         }
     };
+    let mut errors = Vec::new();
 
-    let default_value = if let Some(default) = attrs.iter().find_map(|attr| match attr {
-        Attr::Default(ts) => Some(ts),
-        _ => None,
-    }) {
-        default.clone()
+    let (default_value, defaulted_type) = if let Some(default) =
+        attrs.iter().find_map(|attr| match attr {
+            Attr::Default(ts) => Some(ts),
+            _ => None,
+        }) {
+        (default.clone(), None)
     } else {
-        quote! { ::std::default::Default::default() }
+        (
+            quote! { ::std::default::Default::default() },
+            Some(ty.clone()),
+        )
     };
 
     let name = if let Some(name) = attrs.iter().find_map(|attr| match attr {
@@ -373,35 +845,652 @@ fn derive_field(ident: Ident, default_name: String, field: Field) -> FieldDerive
         default_name
     };
 
-    let (representation, get_named_value, get_self) = if attrs
-        .iter()
-        .any(|attr| matches!(attr, Attr::Skip))
-    {
-        (None, None, None)
+    let min = attrs.iter().find_map(|attr| match attr {
+        Attr::Min(ts) => Some(typed_bound(ts, &ty)),
+        _ => None,
+    });
+    let max = attrs.iter().find_map(|attr| match attr {
+        Attr::Max(ts) => Some(typed_bound(ts, &ty)),
+        _ => None,
+    });
+    let step = attrs.iter().find_map(|attr| match attr {
+        Attr::Step(ts) => Some(ts),
+        _ => None,
+    });
+    let unit = attrs.iter().find_map(|attr| match attr {
+        Attr::Unit(unit) => Some(unit),
+        _ => None,
+    });
+    let group = attrs.iter().find_map(|attr| match attr {
+        Attr::Group(group) => Some(group.value()),
+        _ => None,
+    });
+    // Groups are stored alongside the fields, so they can't share a name with one
+    for attr in &attrs {
+        if let Attr::Group(group) = attr {
+            if labels.iter().any(|(_, label, _)| *label == group.value()) {
+                errors.push(
+                    syn::Error::new(
+                        group.span(),
+                        format!("A field is already named `{}`", group.value()),
+                    )
+                    .to_compile_error(),
+                );
+            }
+        }
+    }
+    let on_change = attrs.iter().find_map(|attr| match attr {
+        Attr::OnChange(ts) => Some(ts.clone()),
+        _ => None,
+    });
+    let validate = attrs.iter().find_map(|attr| match attr {
+        Attr::Validate(ts) => Some(ts),
+        _ => None,
+    });
+    let readonly = attrs.iter().any(|attr| matches!(attr, Attr::Readonly));
+    let skip = attrs.iter().any(|attr| matches!(attr, Attr::Skip));
+    let flatten = attrs.iter().any(|attr| matches!(attr, Attr::Flatten));
+
+    let has_field = if skip {
+        None
+    } else if flatten {
+        // Only structs deriving `LiveMod` can be flattened, so this fails to compile for any other type
+        Some(quote_spanned! { ty.span()=> <#ty as ::livemod::LiveModStruct>::has_field(name) })
     } else {
-        let default_repr = quote! { ::livemod::DefaultRepr };
-        let repr_struct = attrs
+        Some(quote! { name == #name })
+    };
+
+    let (
+        representation,
+        get_named_value,
+        get_self,
+        accept_arm,
+        construct,
+        static_representation,
+        represented_type,
+    ) = if skip {
+        (None, None, None, None, default_value.clone(), None, None)
+    } else if flatten {
+        let representation = quote! {
+            ::livemod::Namespaced::into_structure_fields(
+                ::livemod::LiveMod::repr_default(#ident, ::livemod::ActionTarget::This),
+            )
+        };
+        let get_named_value = quote! {
+            _ if #has_field => ::std::option::Option::Some((#ident as &dyn ::livemod::LiveMod, target))
+        };
+        let get_self = quote! {
+            ::livemod::Namespaced::structure_values(
+                ::livemod::LiveMod::get_self(#ident, ::livemod::ActionTarget::This),
+            )
+        };
+        let accept_arm = quote! {
+            _ if #has_field => (::livemod::LiveMod::accept(#ident, target, value)?, None)
+        };
+        let construct = quote! {
+            <#ty as ::livemod::LiveModCtor>::from_value(
+                value.take_structure_values(&<#ty as ::livemod::LiveModCtor>::repr_static()),
+            )?
+        };
+        let static_representation = quote! {
+            ::livemod::Namespaced::into_structure_fields(
+                <#ty as ::livemod::LiveModCtor>::repr_static(),
+            )
+        };
+        (
+            Some(representation),
+            Some(get_named_value),
+            Some(get_self),
+            Some(accept_arm),
+            construct,
+            Some(static_representation),
+            Some(ty),
+        )
+    } else {
+        let has_repr = attrs.iter().any(|attr| matches!(attr, Attr::Repr(_)));
+        // Errors from a representation which doesn't support the field's type are reported on the attribute
+        let (mut repr_struct, repr_span) = if let Some(repr) =
+            attrs.iter().find_map(|attr| match attr {
+                Attr::Repr(ts) => Some(ts),
+                _ => None,
+            }) {
+            (repr.clone(), repr.span())
+        } else if min.is_some() || max.is_some() {
+            let span = attrs
+                .iter()
+                .find_map(|attr| match attr {
+                    Attr::Min(ts) | Attr::Max(ts) => Some(ts.span()),
+                    _ => None,
+                })
+                .unwrap();
+            let min = option_tokens(&min);
+            let max = option_tokens(&max);
+            (
+                quote! { ::livemod::Bounds::<#ty> { min: #min, max: #max } },
+                span,
+            )
+        } else {
+            (quote! { ::livemod::DefaultRepr }, Span::call_site())
+        };
+        if step.is_some() || unit.is_some() {
+            repr_struct = quote! { ::livemod::Styled::new(#repr_struct) };
+            if let Some(step) = step {
+                repr_struct = quote! { #repr_struct.step(#step) };
+            }
+            if let Some(unit) = unit {
+                repr_struct = quote! { #repr_struct.unit(#unit) };
+            }
+        }
+        let doc = attrs
             .iter()
             .find_map(|attr| match attr {
-                Attr::Repr(ts) => Some(ts),
+                Attr::Tooltip(tooltip) => Some(tooltip.clone()),
                 _ => None,
             })
-            .unwrap_or(&default_repr);
+            .or(doc_comment);
+        let mut parameters = Vec::new();
+        if let Some(doc) = doc {
+            parameters.push(quote! {
+                (String::from("doc"), ::livemod::Parameter::String(String::from(#doc)))
+            });
+        }
+        if readonly {
+            parameters.push(quote! {
+                (String::from("readonly"), ::livemod::Parameter::Bool(true))
+            });
+        }
+        for attr in &attrs {
+            let parameter = match attr {
+                Attr::VisibleIf(condition) => condition_parameter("visible_if", condition, labels),
+                Attr::EnabledIf(condition) => condition_parameter("enabled_if", condition, labels),
+                _ => continue,
+            };
+            match parameter {
+                Ok(parameter) => parameters.push(parameter),
+                Err(error) => errors.push(error.to_compile_error()),
+            }
+        }
+        let representation = with_parameters(
+            quote_spanned! { repr_span=> ::livemod::LiveModRepr::repr(&#repr_struct, #ident) },
+            &parameters,
+        );
         let representation = quote! {
-            (#name.to_owned(), ::livemod::LiveModRepr::repr(&#repr_struct, #ident))
+            ::std::iter::once((#name.to_owned(), #representation))
         };
 
-        let get_named_value = quote! { #name => #ident };
-        let get_self = quote! { (#name.to_owned(), ::livemod::LiveMod::get_self(#ident, ::livemod::ActionTarget::This)) };
-        (Some(representation), Some(get_named_value), Some(get_self))
+        let get_named_value = quote! { #name => ::std::option::Option::Some((#ident as &dyn ::livemod::LiveMod, field_target)) };
+        let get_self = quote! {
+            ::std::iter::once((#name.to_owned(), ::livemod::LiveMod::get_self(#ident, ::livemod::ActionTarget::This)))
+        };
+        // Clamp the accepted value to the declared bounds
+        let clamp = |set_changed: TokenStream| {
+            let clamp_min = min.as_ref().map(|min| {
+                quote! {
+                    if *livemod_field < #min {
+                        *livemod_field = #min;
+                        #set_changed
+                    }
+                }
+            });
+            let clamp_max = max.as_ref().map(|max| {
+                quote! {
+                    if *livemod_field > #max {
+                        *livemod_field = #max;
+                        #set_changed
+                    }
+                }
+            });
+            quote! { #clamp_min #clamp_max }
+        };
+        // Correct the accepted value to fit the representation, such as by wrapping an angle
+        let correct = |set_changed: TokenStream| {
+            if has_repr {
+                Some(quote_spanned! { repr_span=>
+                    if ::livemod::LiveModRepr::correct(&#repr_struct, &mut *livemod_field) {
+                        #set_changed
+                    }
+                })
+            } else {
+                None
+            }
+        };
+        let on_change =
+            option_tokens(&on_change.map(|on_change| quote! { #on_change as fn(&mut Self) }));
+        let accept_arm = if readonly {
+            // Ignore the value, and send the current one back
+            quote! {
+                #name => {
+                    let _ = (field_target, value);
+                    (true, None)
+                }
+            }
+        } else if let Some(validate) = validate {
+            // Apply the value to a copy of the field, so it can be rejected without changing the field. The value is
+            // always sent back, in case it was rejected or corrected, but the hooks only run if it was accepted.
+            let clamp = clamp(quote! {});
+            let correct = correct(quote! {});
+            quote! {
+                #name => {
+                    let mut livemod_candidate = ::std::clone::Clone::clone(&*#ident);
+                    let livemod_field = &mut livemod_candidate;
+                    ::livemod::LiveMod::accept(&mut *livemod_field, field_target, value)?;
+                    #clamp
+                    #correct
+                    if #validate(livemod_field) {
+                        *#ident = livemod_candidate;
+                        (true, #on_change)
+                    } else {
+                        return ::std::result::Result::Ok(true);
+                    }
+                }
+            }
+        } else if min.is_some() || max.is_some() || has_repr {
+            // Return `true` if the value is clamped or corrected, so the corrected value is sent back
+            let clamp = clamp(quote! { livemod_changed = true; });
+            let correct = correct(quote! { livemod_changed = true; });
+            quote! {
+                #name => {
+                    let livemod_field = #ident;
+                    let mut livemod_changed = ::livemod::LiveMod::accept(&mut *livemod_field, field_target, value)?;
+                    #clamp
+                    #correct
+                    (livemod_changed, #on_change)
+                }
+            }
+        } else {
+            quote! {
+                #name => (::livemod::LiveMod::accept(#ident, field_target, value)?, #on_change)
+            }
+        };
+        let construct = quote! {
+            <#ty as ::livemod::LiveModCtor>::from_value(value.parameters.remove(#name)?)?
+        };
+        let static_representation = with_parameters(
+            quote! { <#ty as ::livemod::LiveModCtor>::repr_static() },
+            &parameters,
+        );
+        let static_representation = quote! {
+            ::std::iter::once((#name.to_owned(), #static_representation))
+        };
+        (
+            Some(representation),
+            Some(get_named_value),
+            Some(get_self),
+            Some(accept_arm),
+            construct,
+            Some(static_representation),
+            Some(ty),
+        )
     };
 
     FieldDerive {
+        member,
         ident,
         default_value,
         representation,
         get_named_value,
         get_self,
+        accept_arm,
+        has_field,
+        construct,
+        static_representation,
+        group,
+        represented_type,
+        defaulted_type,
+        errors,
+    }
+}
+
+/// Call the `on_change` hook of the accepted field, if it has one, and then the hook of the container, returning whether
+/// the value should be sent back.
+fn run_on_change(container_on_change: &Option<TokenStream>) -> TokenStream {
+    let container_on_change = match container_on_change {
+        Some(on_change) => quote! {
+            #on_change(self);
+            true
+        },
+        None => quote! { livemod_changed },
+    };
+    quote! {
+        let livemod_changed = match livemod_on_change {
+            Some(livemod_on_change) => {
+                livemod_on_change(self);
+                true
+            }
+            None => livemod_changed,
+        };
+        ::std::result::Result::Ok({ #container_on_change })
+    }
+}
+
+fn option_tokens(value: &Option<TokenStream>) -> TokenStream {
+    match value {
+        Some(value) => quote! { ::std::option::Option::Some(#value) },
+        None => quote! { ::std::option::Option::None },
+    }
+}
+
+/// Give a `min` or `max` bound the type of the field, so that a bound which doesn't fit in the type is a compile error
+/// instead of wrapping around. Number literals without a suffix are given the field's type, so that integer bounds can
+/// be used for float fields.
+fn typed_bound(bound: &TokenStream, ty: &Type) -> TokenStream {
+    const NUMBER_TYPES: &[&str] = &[
+        "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize",
+        "f32", "f64",
+    ];
+    let primitive = match ty {
+        Type::Path(path) if path.qself.is_none() => path
+            .path
+            .get_ident()
+            .map(|ident| ident.to_string())
+            .filter(|ident| NUMBER_TYPES.contains(&ident.as_str())),
+        _ => None,
+    };
+    let mut tokens = bound.clone().into_iter().collect::<Vec<_>>();
+    let negated = tokens.len() == 2
+        && matches!(&tokens[0], TokenTree::Punct(punct) if punct.as_char() == '-');
+    let mut bound = bound.clone();
+    if let (Some(primitive), true) = (primitive, tokens.len() == 1 || negated) {
+        if let Some(TokenTree::Literal(literal)) = tokens.pop() {
+            let literal = match Lit::new(literal) {
+                Lit::Int(int) if int.suffix().is_empty() => {
+                    let digits = format!("{}{}", int.base10_digits(), primitive);
+                    Some(syn::LitInt::new(&digits, int.span()).into_token_stream())
+                }
+                Lit::Float(float) if float.suffix().is_empty() && primitive.starts_with('f') => {
+                    let digits = format!("{}{}", float.base10_digits(), primitive);
+                    Some(syn::LitFloat::new(&digits, float.span()).into_token_stream())
+                }
+                _ => None,
+            };
+            if let Some(literal) = literal {
+                bound = tokens.into_iter().collect();
+                bound.extend(literal);
+            }
+        }
+    }
+    quote_spanned! { bound.span()=>
+        {
+            const BOUND: #ty = #bound;
+            BOUND
+        }
+    }
+}
+
+/// Get the name to display for an enum variant, which may be changed with `#[livemod(rename)]`, and the value to use
+/// when the variant is selected, if one is given with `#[livemod(default)]`.
+fn variant_attrs(
+    variant_name: &Ident,
+    attrs: Vec<Attribute>,
+    rule: Option<RenameRule>,
+    errors: &mut Vec<TokenStream>,
+) -> (String, Option<TokenStream>) {
+    let mut variant_string = variant_name.to_string();
+    if let Some(rule) = rule {
+        variant_string = rule.apply(&variant_string);
+    }
+    let mut default = None;
+    match parse_attrs(&attrs, AttrTarget::Variant) {
+        Ok(attrs) => {
+            for attr in attrs {
+                match attr {
+                    Attr::Rename(name) => variant_string = name,
+                    Attr::Default(ts) => default = Some(ts),
+                    _ => unreachable!("unsupported attributes are rejected by `parse_attrs`"),
+                }
+            }
+        }
+        Err(error) => errors.push(error.to_compile_error()),
+    }
+    (variant_string, default)
+}
+
+/// Collect the lines of a field's `///` doc comment, if it has one.
+fn doc_comment(attrs: &[Attribute]) -> Option<String> {
+    let lines = attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("doc"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(Meta::NameValue(MetaNameValue {
+                lit: Lit::Str(line),
+                ..
+            })) => Some(line.value()),
+            _ => None,
+        })
+        .map(|line| line.strip_prefix(' ').map(str::to_owned).unwrap_or(line))
+        .collect::<Vec<_>>();
+    let doc = lines.join("\n").trim().to_owned();
+    if doc.is_empty() {
+        None
+    } else {
+        Some(doc)
+    }
+}
+
+/// Insert the given `(name, parameter)` pairs into the parameters of a field's representation.
+fn with_parameters(representation: TokenStream, parameters: &[TokenStream]) -> TokenStream {
+    if parameters.is_empty() {
+        representation
+    } else {
+        quote! {
+            {
+                let mut repr = #representation;
+                #(
+                    let (name, parameter) = #parameters;
+                    repr.parameters.insert(name, parameter);
+                )*
+                repr
+            }
+        }
+    }
+}
+
+/// Parse a `visible_if` or `enabled_if` condition of the form `field`, `!field`, `field == Variant` or
+/// `field != Variant` into a parameter of a field's representation, referring to the sibling field by its label.
+fn condition_parameter(
+    kind: &str,
+    condition: &LitStr,
+    labels: &[(String, String, bool)],
+) -> syn::Result<TokenStream> {
+    let value = condition.value();
+    let (field, equals, negate) = if let Some((field, variant)) = value.split_once("==") {
+        (field.trim(), Some(variant.trim()), false)
+    } else if let Some((field, variant)) = value.split_once("!=") {
+        (field.trim(), Some(variant.trim()), true)
+    } else if let Some(field) = value.trim().strip_prefix('!') {
+        (field.trim(), None, true)
+    } else {
+        (value.trim(), None, false)
+    };
+    match labels.iter().find(|(name, ..)| name == field) {
+        // Skipped fields aren't sent to the viewer, so the condition could never be checked
+        Some((_, _, true)) => Err(syn::Error::new(
+            condition.span(),
+            format!(
+                "The field `{}` is skipped, so it can't be used in a condition",
+                field
+            ),
+        )),
+        Some((_, label, false)) => {
+            let equals = option_tokens(&equals.map(|equals| quote! { #equals }));
+            Ok(quote! {
+                (
+                    String::from(#kind),
+                    ::livemod::Parameter::Namespaced(
+                        ::livemod::Namespaced::condition_repr(#label, #equals, #negate),
+                    ),
+                )
+            })
+        }
+        None => Err(syn::Error::new(
+            condition.span(),
+            format!("No field named `{}`", field),
+        )),
+    }
+}
+
+/// Add the given bound to each type parameter used in a represented field, and a `Default` bound to the type of each
+/// field which is constructed with `Default::default()` and depends on a type parameter.
+fn add_trait_bounds(
+    mut generics: Generics,
+    bound: Path,
+    represented_types: &[Type],
+    defaulted_types: &[Type],
+) -> Generics {
+    let type_params = generics
+        .type_params()
+        .map(|param| param.ident.clone())
+        .collect::<Vec<_>>();
+    for param in &mut generics.params {
+        if let GenericParam::Type(param) = param {
+            if represented_types
+                .iter()
+                .any(|ty| mentions_ident(ty.to_token_stream(), &param.ident))
+            {
+                param.bounds.push(parse_quote!(#bound));
+            }
+        }
+    }
+    let where_clause = generics.make_where_clause();
+    for ty in defaulted_types {
+        if type_params
+            .iter()
+            .any(|param| mentions_ident(ty.to_token_stream(), param))
+        {
+            where_clause
+                .predicates
+                .push(parse_quote!(#ty: ::std::default::Default));
+        }
+    }
+    generics
+}
+
+fn mentions_ident(tokens: TokenStream, ident: &Ident) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(token) => token == *ident,
+        TokenTree::Group(group) => mentions_ident(group.stream(), ident),
+        _ => false,
+    })
+}
+
+/// Attributes which apply to the whole struct or enum
+struct ContainerAttrs {
+    rename_all: Option<RenameRule>,
+    label: Option<String>,
+    on_change: Option<TokenStream>,
+}
+
+impl ContainerAttrs {
+    fn from_attrs(attrs: &[Attribute]) -> syn::Result<ContainerAttrs> {
+        let mut container = ContainerAttrs {
+            rename_all: None,
+            label: None,
+            on_change: None,
+        };
+        for attr in parse_attrs(attrs, AttrTarget::Container)? {
+            match attr {
+                Attr::RenameAll(rule) => container.rename_all = Some(rule),
+                Attr::Label(label) => container.label = Some(label),
+                Attr::OnChange(on_change) => container.on_change = Some(on_change),
+                _ => unreachable!("unsupported attributes are rejected by `parse_attrs`"),
+            }
+        }
+        Ok(container)
+    }
+}
+
+/// A naming convention which field and variant names can be converted to
+#[derive(Clone, Copy)]
+enum RenameRule {
+    /// `Sentence case`, used for field names by default
+    Sentence,
+    Title,
+    Lower,
+    Upper,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    Camel,
+    Pascal,
+}
+
+impl RenameRule {
+    fn from_str(rule: &str) -> Option<RenameRule> {
+        Some(match rule {
+            "Sentence case" => RenameRule::Sentence,
+            "Title Case" => RenameRule::Title,
+            "lowercase" => RenameRule::Lower,
+            "UPPERCASE" => RenameRule::Upper,
+            "snake_case" => RenameRule::Snake,
+            "SCREAMING_SNAKE_CASE" => RenameRule::ScreamingSnake,
+            "kebab-case" => RenameRule::Kebab,
+            "camelCase" => RenameRule::Camel,
+            "PascalCase" => RenameRule::Pascal,
+            _ => return None,
+        })
+    }
+
+    /// Convert a `snake_case` or `PascalCase` identifier to this naming convention.
+    fn apply(self, ident: &str) -> String {
+        let words = split_words(ident);
+        match self {
+            RenameRule::Sentence => capitalise(&words.join(" ").to_lowercase()),
+            RenameRule::Title => words
+                .iter()
+                .map(|word| capitalise(&word.to_lowercase()))
+                .collect::<Vec<_>>()
+                .join(" "),
+            RenameRule::Lower => words.concat().to_lowercase(),
+            RenameRule::Upper => words.concat().to_uppercase(),
+            RenameRule::Snake => words.join("_").to_lowercase(),
+            RenameRule::ScreamingSnake => words.join("_").to_uppercase(),
+            RenameRule::Kebab => words.join("-").to_lowercase(),
+            RenameRule::Camel => {
+                let pascal = RenameRule::Pascal.apply(ident);
+                let mut chars = pascal.chars();
+                match chars.next() {
+                    Some(first) => first.to_lowercase().chain(chars).collect(),
+                    None => String::new(),
+                }
+            }
+            RenameRule::Pascal => words
+                .iter()
+                .map(|word| capitalise(&word.to_lowercase()))
+                .collect(),
+        }
+    }
+}
+
+/// Split an identifier into words at underscores and at the start of each capitalised word.
+fn split_words(ident: &str) -> Vec<String> {
+    let mut words = vec![];
+    let mut word = String::new();
+    let mut prev_lowercase = false;
+    for c in ident.chars() {
+        if c == '_' {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            prev_lowercase = false;
+            continue;
+        }
+        if c.is_uppercase() && prev_lowercase && !word.is_empty() {
+            words.push(std::mem::take(&mut word));
+        }
+        prev_lowercase = c.is_lowercase() || c.is_numeric();
+        word.push(c);
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+fn capitalise(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
     }
 }
 
@@ -410,33 +1499,239 @@ enum Attr {
     Rename(String),
     Repr(TokenStream),
     Default(TokenStream),
+    Tooltip(String),
+    Min(TokenStream),
+    Max(TokenStream),
+    Step(TokenStream),
+    Unit(String),
+    RenameAll(RenameRule),
+    Label(String),
+    Flatten,
+    Group(LitStr),
+    OnChange(TokenStream),
+    Validate(TokenStream),
+    Readonly,
+    VisibleIf(LitStr),
+    EnabledIf(LitStr),
 }
 
-impl Parse for Attr {
+/// The items a `#[livemod]` attribute can be placed on
+#[derive(Clone, Copy)]
+enum AttrTarget {
+    Field,
+    Variant,
+    Container,
+}
+
+impl AttrTarget {
+    fn supported_keys(self) -> &'static [&'static str] {
+        match self {
+            AttrTarget::Field => &[
+                "skip",
+                "rename",
+                "repr",
+                "default",
+                "tooltip",
+                "min",
+                "max",
+                "step",
+                "unit",
+                "flatten",
+                "group",
+                "on_change",
+                "validate",
+                "readonly",
+                "visible_if",
+                "enabled_if",
+            ],
+            AttrTarget::Variant => &["rename", "default"],
+            AttrTarget::Container => &["rename_all", "label", "on_change"],
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            AttrTarget::Field => "fields",
+            AttrTarget::Variant => "enum variants",
+            AttrTarget::Container => "structs and enums",
+        }
+    }
+}
+
+/// Whether the two attribute keys can't be given on the same field.
+fn conflicting_keys(a: &str, b: &str) -> bool {
+    let conflicts = |a: &str, b: &str| match a {
+        // Only the default value of a skipped field is used
+        "skip" => b != "default",
+        // The attributes of a flattened field's own fields are used instead
+        "flatten" => !matches!(b, "default" | "group"),
+        "readonly" => matches!(b, "validate" | "on_change"),
+        _ => false,
+    };
+    conflicts(a, b) || conflicts(b, a)
+}
+
+/// Parse all of the `#[livemod]` attributes in `attrs`, checking that each key is supported on `target`, is given
+/// at most once, and doesn't conflict with any other key.
+fn parse_attrs(attrs: &[Attribute], target: AttrTarget) -> syn::Result<Vec<Attr>> {
+    let mut parsed: Vec<(Ident, Attr)> = Vec::new();
+    let mut error: Option<syn::Error> = None;
+    let mut push_error = |new: syn::Error| match &mut error {
+        Some(error) => error.combine(new),
+        None => error = Some(new),
+    };
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("livemod")) {
+        let attrs = match syn::parse2::<Attrs>(attr.tokens.clone()) {
+            Ok(attrs) => attrs,
+            Err(error) => {
+                push_error(error);
+                continue;
+            }
+        };
+        for (key, attr) in attrs.0 {
+            let key_string = key.to_string();
+            if !target.supported_keys().contains(&key_string.as_str()) {
+                push_error(syn::Error::new(
+                    key.span(),
+                    format!(
+                        "`{}` is not supported on {}",
+                        key_string,
+                        target.description()
+                    ),
+                ));
+            } else if parsed.iter().any(|(other, _)| *other == key) {
+                push_error(syn::Error::new(
+                    key.span(),
+                    format!("Duplicate `{}` attribute", key_string),
+                ));
+            } else if let Some((other, _)) = parsed
+                .iter()
+                .find(|(other, _)| conflicting_keys(&key_string, &other.to_string()))
+            {
+                push_error(syn::Error::new(
+                    key.span(),
+                    format!("`{}` can't be combined with `{}`", key_string, other),
+                ));
+            } else {
+                parsed.push((key, attr));
+            }
+        }
+    }
+    match error {
+        Some(error) => Err(error),
+        None => Ok(parsed.into_iter().map(|(_, attr)| attr).collect()),
+    }
+}
+
+/// The comma-separated contents of a `#[livemod(...)]` attribute, along with the key of each attribute
+struct Attrs(Vec<(Ident, Attr)>);
+
+impl Parse for Attrs {
     fn parse(direct_input: syn::parse::ParseStream) -> syn::Result<Self> {
         let input;
         parenthesized!(input in direct_input);
+        let attrs =
+            Punctuated::<(Ident, Attr), Token![,]>::parse_terminated_with(&input, |input| {
+                let key = input.fork().parse::<Ident>()?;
+                Ok((key, input.parse()?))
+            })?;
+        Ok(Attrs(attrs.into_iter().collect()))
+    }
+}
+
+impl Parse for Attr {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
         let attr_type: Ident = input.parse()?;
         if attr_type == "skip" {
-            if !input.is_empty() {
-                return Err(input.error("Expected end of attribute content"));
+            if !input.is_empty() && !input.peek(Token![,]) {
+                return Err(input.error(format!("`{}` doesn't take a value", attr_type)));
             }
             Ok(Attr::Skip)
+        } else if attr_type == "flatten" {
+            if !input.is_empty() && !input.peek(Token![,]) {
+                return Err(input.error(format!("`{}` doesn't take a value", attr_type)));
+            }
+            Ok(Attr::Flatten)
+        } else if attr_type == "readonly" {
+            if !input.is_empty() && !input.peek(Token![,]) {
+                return Err(input.error(format!("`{}` doesn't take a value", attr_type)));
+            }
+            Ok(Attr::Readonly)
+        } else if attr_type == "visible_if" {
+            input.parse::<Token![=]>()?;
+            Ok(Attr::VisibleIf(input.parse()?))
+        } else if attr_type == "enabled_if" {
+            input.parse::<Token![=]>()?;
+            Ok(Attr::EnabledIf(input.parse()?))
         } else if attr_type == "rename" {
             input.parse::<Token![=]>()?;
             let new_name: LitStr = input.parse()?;
             Ok(Attr::Rename(new_name.value()))
         } else if attr_type == "repr" {
             input.parse::<Token![=]>()?;
-            Ok(Attr::Repr(input.parse()?))
+            Ok(Attr::Repr(parse_until_comma(input)?))
         } else if attr_type == "default" {
             input.parse::<Token![=]>()?;
-            Ok(Attr::Default(input.parse()?))
+            Ok(Attr::Default(parse_until_comma(input)?))
+        } else if attr_type == "tooltip" {
+            input.parse::<Token![=]>()?;
+            let tooltip: LitStr = input.parse()?;
+            Ok(Attr::Tooltip(tooltip.value()))
+        } else if attr_type == "min" {
+            input.parse::<Token![=]>()?;
+            Ok(Attr::Min(parse_until_comma(input)?))
+        } else if attr_type == "max" {
+            input.parse::<Token![=]>()?;
+            Ok(Attr::Max(parse_until_comma(input)?))
+        } else if attr_type == "step" {
+            input.parse::<Token![=]>()?;
+            Ok(Attr::Step(parse_until_comma(input)?))
+        } else if attr_type == "rename_all" {
+            input.parse::<Token![=]>()?;
+            let rule: LitStr = input.parse()?;
+            RenameRule::from_str(&rule.value())
+                .map(Attr::RenameAll)
+                .ok_or_else(|| {
+                    syn::Error::new(
+                        rule.span(),
+                        "Unknown naming convention, expected one of \"Sentence case\", \"Title Case\", \"lowercase\", \"UPPERCASE\", \"snake_case\", \"SCREAMING_SNAKE_CASE\", \"kebab-case\", \"camelCase\" or \"PascalCase\"",
+                    )
+                })
+        } else if attr_type == "group" {
+            input.parse::<Token![=]>()?;
+            Ok(Attr::Group(input.parse()?))
+        } else if attr_type == "on_change" {
+            input.parse::<Token![=]>()?;
+            Ok(Attr::OnChange(parse_until_comma(input)?))
+        } else if attr_type == "validate" {
+            input.parse::<Token![=]>()?;
+            Ok(Attr::Validate(parse_until_comma(input)?))
+        } else if attr_type == "label" {
+            input.parse::<Token![=]>()?;
+            let label: LitStr = input.parse()?;
+            Ok(Attr::Label(label.value()))
+        } else if attr_type == "unit" {
+            input.parse::<Token![=]>()?;
+            let unit: LitStr = input.parse()?;
+            Ok(Attr::Unit(unit.value()))
         } else {
             Err(syn::Error::new(
                 attr_type.span(),
-                "Unrecognised attribute tag",
+                format!("Unknown livemod attribute `{}`", attr_type),
             ))
         }
     }
 }
+
+/// Parse the tokens of an expression, up to the next comma which isn't nested in a group.
+fn parse_until_comma(input: syn::parse::ParseStream) -> syn::Result<TokenStream> {
+    let mut tokens = TokenStream::new();
+    while !input.is_empty() && !input.peek(Token![,]) {
+        tokens.extend(std::iter::once(input.parse::<TokenTree>()?));
+    }
+    if tokens.is_empty() {
+        Err(input.error("Expected an expression"))
+    } else {
+        Ok(tokens)
+    }
+}
@@ -0,0 +1,246 @@
+use livemod::{AcceptError, ActionTarget, LiveMod, Parameter};
+
+#[derive(LiveMod)]
+struct Camera {
+    #[livemod(min = 10, max = 120)]
+    fov: i32,
+    #[livemod(min = 0.0)]
+    near: f32,
+    #[livemod(validate = Camera::valid_far, on_change = Camera::update)]
+    far: f32,
+    #[livemod(readonly)]
+    frame: u32,
+    #[livemod(flatten)]
+    output: Output,
+    #[livemod(group = "Debug", rename = "Show axes")]
+    axes: bool,
+    #[livemod(skip)]
+    updates: u32,
+}
+
+impl Camera {
+    fn new() -> Camera {
+        Camera {
+            fov: 90,
+            near: 0.1,
+            far: 100.0,
+            frame: 0,
+            output: Output {
+                width: 640,
+                height: 480,
+            },
+            axes: false,
+            updates: 0,
+        }
+    }
+
+    fn valid_far(far: &mut f32) -> bool {
+        // Round to whole units, and reject values behind the camera
+        *far = far.round();
+        *far > 0.0
+    }
+
+    fn update(&mut self) {
+        self.updates += 1;
+    }
+}
+
+#[derive(LiveMod)]
+struct Output {
+    width: u32,
+    height: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, LiveMod)]
+enum Shape {
+    Point,
+    Circle {
+        #[livemod(default = 2.0)]
+        radius: f32,
+    },
+    #[livemod(default = Shape::Rect(3, 4))]
+    Rect(u32, u32),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, LiveMod)]
+enum Quality {
+    Low,
+    #[livemod(rename = "Very high")]
+    High,
+}
+
+fn set(
+    value: &mut dyn LiveMod,
+    path: &[&str],
+    param: Parameter<livemod::Value>,
+) -> Result<bool, AcceptError> {
+    value.accept(ActionTarget::Field(path), param)
+}
+
+fn variant(name: &str) -> Parameter<livemod::Value> {
+    Parameter::String(name.to_owned())
+}
+
+#[test]
+fn bounds_are_clamped() {
+    let mut camera = Camera::new();
+    assert_eq!(
+        set(&mut camera, &["Fov"], Parameter::SignedInt(200)),
+        Ok(true)
+    );
+    assert_eq!(camera.fov, 120);
+    assert_eq!(
+        set(&mut camera, &["Fov"], Parameter::SignedInt(60)),
+        Ok(false)
+    );
+    assert_eq!(camera.fov, 60);
+    assert_eq!(
+        set(&mut camera, &["Fov"], Parameter::SignedInt(-5)),
+        Ok(true)
+    );
+    assert_eq!(camera.fov, 10);
+    assert_eq!(
+        set(&mut camera, &["Near"], Parameter::Float(-1.0)),
+        Ok(true)
+    );
+    assert_eq!(camera.near, 0.0);
+    assert_eq!(
+        set(&mut camera, &["Near"], Parameter::Float(1e9)),
+        Ok(false)
+    );
+    assert_eq!(camera.near, 1e9);
+}
+
+#[test]
+fn validate_corrects_and_rejects_values() {
+    let mut camera = Camera::new();
+    assert_eq!(set(&mut camera, &["Far"], Parameter::Float(50.4)), Ok(true));
+    assert_eq!(camera.far, 50.0);
+    assert_eq!(camera.updates, 1);
+    assert!(set(&mut camera, &["Far"], Parameter::Float(-10.0)).is_ok());
+    assert_eq!(camera.far, 50.0);
+    // on_change isn't run for rejected values
+    assert_eq!(camera.updates, 1);
+}
+
+#[test]
+fn readonly_fields_are_not_modified() {
+    let mut camera = Camera::new();
+    // The current value is sent back instead
+    assert_eq!(
+        set(&mut camera, &["Frame"], Parameter::UnsignedInt(5)),
+        Ok(true)
+    );
+    assert_eq!(camera.frame, 0);
+}
+
+#[test]
+fn fields_are_found_by_label() {
+    let mut camera = Camera::new();
+    assert_eq!(
+        set(&mut camera, &["Width"], Parameter::UnsignedInt(800)),
+        Ok(false)
+    );
+    assert_eq!(camera.output.width, 800);
+    assert_eq!(
+        set(&mut camera, &["Show axes"], Parameter::Bool(true)),
+        Ok(false)
+    );
+    assert!(camera.axes);
+    assert_eq!(
+        set(&mut camera, &["Updates"], Parameter::UnsignedInt(1)),
+        Err(AcceptError::UnknownField("Updates".to_owned()))
+    );
+    assert_eq!(
+        set(&mut camera, &["Fov"], Parameter::Float(1.0)),
+        Err(AcceptError::WrongType {
+            expected: "a signed integer"
+        })
+    );
+    assert_eq!(
+        camera.accept(ActionTarget::This, Parameter::UnsignedInt(1)),
+        Err(AcceptError::MissingField)
+    );
+}
+
+#[test]
+fn unknown_fields_have_empty_values() {
+    let camera = Camera::new();
+    assert_eq!(
+        camera.get_self(ActionTarget::Field(&["Zoom"])).serialize(),
+        "nlivemod:struct{}"
+    );
+    assert_eq!(
+        camera.repr_default(ActionTarget::Field(&["Zoom"])).name,
+        ["livemod", "struct"]
+    );
+    assert_eq!(
+        camera
+            .get_self(ActionTarget::Field(&["Width"]))
+            .as_unsigned_int(),
+        Some(&640)
+    );
+}
+
+#[test]
+fn enum_variants_use_defaults() {
+    let mut shape = Shape::Point;
+    assert_eq!(set(&mut shape, &["variant"], variant("Circle")), Ok(true));
+    assert_eq!(shape, Shape::Circle { radius: 2.0 });
+    assert_eq!(
+        set(&mut shape, &["current", "Radius"], Parameter::Float(5.0)),
+        Ok(false)
+    );
+    assert_eq!(shape, Shape::Circle { radius: 5.0 });
+    assert_eq!(set(&mut shape, &["variant"], variant("Rect")), Ok(true));
+    assert_eq!(shape, Shape::Rect(3, 4));
+    assert_eq!(
+        set(&mut shape, &["variant"], variant("Square")),
+        Err(AcceptError::UnknownVariant("Square".to_owned()))
+    );
+    assert_eq!(shape, Shape::Rect(3, 4));
+}
+
+#[test]
+fn fieldless_enums_use_variant_labels() {
+    let mut quality = Quality::Low;
+    assert_eq!(
+        set(&mut quality, &["variant"], variant("Very high")),
+        Ok(true)
+    );
+    assert_eq!(quality, Quality::High);
+    assert_eq!(
+        set(&mut quality, &["variant"], variant("High")),
+        Err(AcceptError::UnknownVariant("High".to_owned()))
+    );
+}
+
+#[test]
+fn stashed_enums_keep_variant_values() {
+    let mut shape = livemod::Stashed::new(Shape::Circle { radius: 1.0 });
+    assert_eq!(set(&mut shape, &["variant"], variant("Point")), Ok(true));
+    assert_eq!(*shape, Shape::Point);
+    assert_eq!(set(&mut shape, &["variant"], variant("Circle")), Ok(true));
+    assert_eq!(*shape, Shape::Circle { radius: 1.0 });
+}
+
+#[derive(LiveMod)]
+struct Clashing {
+    width: u32,
+    #[livemod(flatten)]
+    output: Output,
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "More than one field is named `Width`")]
+fn flattened_names_must_be_unique() {
+    let clashing = Clashing {
+        width: 1,
+        output: Output {
+            width: 2,
+            height: 3,
+        },
+    };
+    clashing.repr_default(ActionTarget::This);
+}
@@ -0,0 +1,17 @@
+use livemod::LiveMod;
+
+#[derive(LiveMod)]
+struct Data {
+    #[livemod(skip, repr = Slider(0..=10))]
+    value: u32,
+    #[livemod(readonly, validate = Data::check)]
+    other: u32,
+}
+
+impl Data {
+    fn check(_: &mut u32) -> bool {
+        true
+    }
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use livemod::LiveModCtor;
+
+#[derive(LiveModCtor)]
+enum Key {
+    Named(String),
+    Anonymous,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use livemod::{LiveMod, LiveModCtor};
+
+#[derive(PartialEq, Eq, Hash, LiveMod, LiveModCtor)]
+enum Team {
+    Red,
+    #[livemod(default = Team::Red)]
+    Blue,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use livemod::LiveMod;
+
+#[derive(LiveMod)]
+struct Data {
+    #[livemod(rename = "First")]
+    #[livemod(rename = "Second")]
+    value: u32,
+}
+
+fn main() {}
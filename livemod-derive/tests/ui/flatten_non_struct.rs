@@ -0,0 +1,15 @@
+use livemod::LiveMod;
+
+#[derive(LiveMod)]
+enum Shape {
+    Point,
+    Circle { radius: f32 },
+}
+
+#[derive(LiveMod)]
+struct Data {
+    #[livemod(flatten)]
+    shape: Shape,
+}
+
+fn main() {}
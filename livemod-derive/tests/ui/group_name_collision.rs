@@ -0,0 +1,10 @@
+use livemod::LiveMod;
+
+#[derive(LiveMod)]
+struct Data {
+    speed: f32,
+    #[livemod(group = "Speed")]
+    acceleration: f32,
+}
+
+fn main() {}
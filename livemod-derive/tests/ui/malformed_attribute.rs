@@ -0,0 +1,13 @@
+use livemod::LiveMod;
+
+#[derive(LiveMod)]
+struct Data {
+    #[livemod(rename = 5)]
+    value: u32,
+    #[livemod(skip = true)]
+    other: u32,
+    #[livemod(min)]
+    bounded: u32,
+}
+
+fn main() {}
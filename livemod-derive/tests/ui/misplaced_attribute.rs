@@ -0,0 +1,17 @@
+use livemod::LiveMod;
+
+#[derive(LiveMod)]
+#[livemod(skip)]
+struct Data {
+    #[livemod(rename_all = "Title Case")]
+    value: u32,
+}
+
+#[derive(LiveMod)]
+enum Choice {
+    #[livemod(tooltip = "The first choice")]
+    First,
+    Second,
+}
+
+fn main() {}
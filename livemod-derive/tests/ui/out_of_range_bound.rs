@@ -0,0 +1,9 @@
+use livemod::LiveMod;
+
+#[derive(LiveMod)]
+struct Data {
+    #[livemod(min = -1, max = 10)]
+    count: u8,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use livemod::{LiveMod, Slider};
+
+#[derive(LiveMod)]
+struct Data {
+    #[livemod(repr = Slider(0..=10))]
+    name: String,
+}
+
+fn main() {}
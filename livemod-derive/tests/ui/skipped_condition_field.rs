@@ -0,0 +1,11 @@
+use livemod::LiveMod;
+
+#[derive(LiveMod)]
+struct Data {
+    #[livemod(skip)]
+    enabled: bool,
+    #[livemod(visible_if = "enabled")]
+    value: u32,
+}
+
+fn main() {}
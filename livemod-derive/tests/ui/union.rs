@@ -0,0 +1,15 @@
+use livemod::{LiveMod, LiveModCtor};
+
+#[derive(LiveMod)]
+union Data {
+    integer: u32,
+    float: f32,
+}
+
+#[derive(LiveModCtor)]
+union Other {
+    integer: u32,
+    float: f32,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use livemod::LiveMod;
+
+#[derive(LiveMod)]
+struct Data {
+    #[livemod(hidden)]
+    value: u32,
+}
+
+fn main() {}
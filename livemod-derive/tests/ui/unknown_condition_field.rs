@@ -0,0 +1,10 @@
+use livemod::LiveMod;
+
+#[derive(LiveMod)]
+struct Data {
+    enabled: bool,
+    #[livemod(visible_if = "enable")]
+    value: u32,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use livemod::LiveMod;
+
+#[derive(LiveMod)]
+#[livemod(rename_all = "Sponge Case")]
+struct Data {
+    value: u32,
+}
+
+fn main() {}
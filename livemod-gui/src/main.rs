@@ -1,19 +1,69 @@
-use std::collections::HashMap;
-use std::convert::{TryInto};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::io::{BufRead, BufReader, Read};
 use std::sync::mpsc::{self, Sender};
 
 use glium::glutin;
 use hashlink::LinkedHashMap;
-use livemod::{Namespaced, Parameter, Repr, Value};
+use livemod::{Namespaced, NumberStyle, Parameter, Repr, Value};
 
 #[derive(Default)]
 struct State {
     tracked_vars: LinkedHashMap<String, Namespaced<Repr>>,
     tracked_data: HashMap<String, AnyData>,
+    /// Values rejected by the program, with the name of the value they were sent to.
+    errors: Vec<(String, String)>,
+    map_views: HashMap<String, MapView>,
+    /// Only show the values with a path matching this text.
+    search: String,
+    /// The repr of each variable when it was first received.
+    initial_vars: HashMap<String, Namespaced<Repr>>,
+    /// The values of each variable when it was first received, stored in the same way as `tracked_data`.
+    initial_data: HashMap<String, AnyData>,
+    /// Changes which can be undone, with the most recent last.
+    undo: Vec<Edit>,
+    /// Changes which have been undone and can be redone, with the most recently undone last.
+    redo: Vec<Edit>,
+    /// The recent values of each value being plotted, with the time they were shown, oldest first.
+    plots: HashMap<String, VecDeque<(f64, f64)>>,
+    /// The namespaces of the keys being entered to insert into maps, which are only stored by the viewer.
+    drafts: HashSet<String>,
+    /// The paths of the values which are different to their initial values, and of every namespace containing them.
+    modified: HashSet<String>,
+    /// The values changed this frame, from before they were changed, to be able to undo the changes.
+    previous: HashMap<String, AnyData>,
+    /// Whether the values being drawn can't be edited, such as the fields of a read-only struct.
+    disabled: bool,
 }
 
-#[derive(Debug, Clone)]
+/// The values changed together in a single frame, which are undone and redone together.
+struct Edit {
+    /// The path, previous value and new value of each changed value.
+    changes: Vec<(String, Parameter<Value>, Parameter<Value>)>,
+    /// When the values were last changed.
+    time: f64,
+    /// Whether the values were changed while the pointer was held down, such as while dragging a slider.
+    pointer_down: bool,
+}
+
+/// The maximum number of changes which can be undone.
+const UNDO_LIMIT: usize = 1000;
+
+/// Changes to the same text less than this many seconds apart are undone together, as they are typed.
+const UNDO_MERGE_TIME: f64 = 1.0;
+
+/// How many seconds of history are shown in a value's plot.
+const PLOT_TIME: f64 = 10.0;
+
+/// How the entries of a map are listed.
+#[derive(Default)]
+struct MapView {
+    /// Only show entries with a key containing this text.
+    filter: String,
+    /// Sort the entries by their keys, instead of showing them in the map's order.
+    sorted: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
 enum AnyData {
     SignedInt(i64),
     UnsignedInt(u64),
@@ -31,14 +81,6 @@ impl AnyData {
         }
     }
 
-    fn as_signed_int_mut(&mut self) -> Option<&mut i64> {
-        if let Self::SignedInt(v) = self {
-            Some(v)
-        } else {
-            None
-        }
-    }
-
     fn as_unsigned_int(&self) -> Option<&u64> {
         if let Self::UnsignedInt(v) = self {
             Some(v)
@@ -63,11 +105,13 @@ impl AnyData {
         }
     }
 
-    fn as_float_mut(&mut self) -> Option<&mut f64> {
-        if let Self::Float(v) = self {
-            Some(v)
-        } else {
-            None
+    /// The value as a float, if it is a number, to be plotted.
+    fn to_f64(&self) -> Option<f64> {
+        match self {
+            Self::SignedInt(v) => Some(*v as f64),
+            Self::UnsignedInt(v) => Some(*v as f64),
+            Self::Float(v) => Some(*v),
+            _ => None,
         }
     }
 
@@ -104,6 +148,18 @@ impl AnyData {
     }
 }
 
+impl std::fmt::Display for AnyData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AnyData::SignedInt(v) => write!(f, "{}", v),
+            AnyData::UnsignedInt(v) => write!(f, "{}", v),
+            AnyData::Float(v) => write!(f, "{}", v),
+            AnyData::Bool(v) => write!(f, "{}", v),
+            AnyData::String(v) => write!(f, "{}", v),
+        }
+    }
+}
+
 impl From<AnyData> for Parameter<Value> {
     fn from(data: AnyData) -> Self {
         match data {
@@ -141,9 +197,10 @@ fn main() {
         .unwrap();
 
     let (sender, recv) = mpsc::channel();
-    std::thread::spawn(|| reader_thread(sender));
 
     let event_loop = glutin::event_loop::EventLoop::with_user_event();
+    let proxy = event_loop.create_proxy();
+    std::thread::spawn(|| reader_thread(sender, proxy));
     let display = create_display(&event_loop);
 
     let mut egui = egui_glium::EguiGlium::new(&display);
@@ -154,46 +211,113 @@ fn main() {
 
     event_loop.run(move |event, _, control_flow| match event {
         glutin::event::Event::MainEventsCleared => {
-            while let Ok(msg) = recv.try_recv() {
-                fn recursive_insert(namespace: String, param: Parameter<Value>, state: &mut State) {
-                    let parameter = match param {
-                        Parameter::SignedInt(value) => AnyData::SignedInt(value),
-                        Parameter::UnsignedInt(value) => AnyData::UnsignedInt(value),
-                        Parameter::Float(value) => AnyData::Float(value),
-                        Parameter::Bool(value) => AnyData::Bool(value),
-                        Parameter::String(value) => AnyData::String(value),
-                        Parameter::Namespaced(namespaced) => {
-                            for (name, param) in namespaced.parameters.into_iter() {
-                                recursive_insert(format!("{}.{}", namespace, name), param, state);
-                            }
-                            return;
-                        }
-                    };
-                    state.tracked_data.insert(namespace, parameter);
-                }
+            egui.begin_frame(&display);
+            let time = egui.ctx().input().time;
 
+            while let Ok(msg) = recv.try_recv() {
+                // Show the new values, even if nothing else has changed
+                egui.ctx().request_repaint();
                 match msg {
                     Message::NewData(name, data, initial_value) => {
-                        recursive_insert(format!(".{}", name), initial_value, &mut state);
+                        let namespace = format!(".{}", name);
+                        recursive_insert(
+                            namespace.clone(),
+                            initial_value.clone(),
+                            &mut state.tracked_data,
+                        );
+                        store_lengths(&data, &namespace, &mut state.tracked_data);
+                        recursive_insert(namespace.clone(), initial_value, &mut state.initial_data);
+                        store_lengths(&data, &namespace, &mut state.initial_data);
+                        state.initial_vars.insert(name.clone(), data.clone());
                         state.tracked_vars.insert(name, data);
                     }
                     Message::UpdateRepr(name, data, value) => {
-                        recursive_insert(format!(".{}", name), value, &mut state);
+                        let namespace = format!(".{}", name);
+                        // Forget any values which have been removed, such as the fields of another variant
+                        let drafts = &state.drafts;
+                        state.tracked_data.retain(|path, _| {
+                            !is_inside(path, &namespace) || is_draft(path, drafts)
+                        });
+                        recursive_insert(namespace.clone(), value, &mut state.tracked_data);
+                        store_lengths(&data, &namespace, &mut state.tracked_data);
+                        record_history(&namespace, time, &mut state);
                         *state.tracked_vars.get_mut(&name).unwrap() = data;
                     }
                     Message::UpdateData(name, value) => {
-                        recursive_insert(name, value, &mut state);
+                        let namespace = format!(".{}", name);
+                        recursive_insert(namespace.clone(), value, &mut state.tracked_data);
+                        record_history(&namespace, time, &mut state);
                     }
                     Message::RemoveData(name) => {
                         state.tracked_vars.remove(&name);
                     }
+                    Message::AcceptError(name, error) => {
+                        state.errors.push((name, error));
+                    }
                     Message::Quit => {
                         quit = true;
                     }
                 }
             }
 
-            egui.begin_frame(&display);
+            state.modified = find_modified(&state);
+
+            let (reset_all, mut undo, mut redo) = egui::TopBottomPanel::top("search")
+                .show(egui.ctx(), |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label("Search:");
+                        ui.text_edit_singleline(&mut state.search);
+                        if ui.small_button("Clear").clicked() {
+                            state.search.clear();
+                        }
+                        ui.separator();
+                        let undo = ui
+                            .add(egui::Button::new("Undo").enabled(!state.undo.is_empty()))
+                            .on_hover_text("Ctrl+Z")
+                            .clicked();
+                        let redo = ui
+                            .add(egui::Button::new("Redo").enabled(!state.redo.is_empty()))
+                            .on_hover_text("Ctrl+Shift+Z")
+                            .clicked();
+                        let reset_all = ui
+                            .button("Reset all")
+                            .on_hover_text("Change every value back to its initial value")
+                            .clicked();
+                        (reset_all, undo, redo)
+                    })
+                    .inner
+                })
+                .inner;
+            {
+                let ctx = egui.ctx();
+                let input = ctx.input();
+                // Leave the shortcut to text boxes while they are being edited
+                if input.modifiers.command
+                    && input.key_pressed(egui::Key::Z)
+                    && !ctx.wants_keyboard_input()
+                {
+                    if input.modifiers.shift {
+                        redo = true;
+                    } else {
+                        undo = true;
+                    }
+                }
+            }
+            state.previous.clear();
+
+            if !state.errors.is_empty() {
+                egui::TopBottomPanel::bottom("errors").show(egui.ctx(), |ui| {
+                    for (name, error) in &state.errors {
+                        ui.colored_label(
+                            egui::Color32::RED,
+                            format!("Couldn't set {}: {}", name, error),
+                        );
+                    }
+                    if ui.button("Dismiss").clicked() {
+                        state.errors.clear();
+                    }
+                });
+            }
 
             let messages = egui::CentralPanel::default()
                 .show(egui.ctx(), |ui| {
@@ -222,14 +346,64 @@ fn main() {
                 })
                 .inner;
 
+            let mut messages = messages;
+            if reset_all {
+                let namespaces = state
+                    .initial_vars
+                    .keys()
+                    .filter(|name| state.tracked_vars.contains_key(*name))
+                    .map(|name| format!(".{}", name))
+                    .collect::<Vec<_>>();
+                for namespace in namespaces {
+                    messages.append(&mut reset_value(&namespace, &mut state));
+                }
+            }
+            let pointer_down = egui.ctx().input().pointer.any_down();
+            record_edit(&messages, time, pointer_down, &mut state);
+            if undo {
+                if let Some(edit) = state.undo.pop() {
+                    let mut undo_msgs: Messages = edit
+                        .changes
+                        .iter()
+                        .rev()
+                        .map(|(name, old, _)| (name.clone(), old.clone()))
+                        .collect();
+                    store_messages(&undo_msgs, &mut state);
+                    messages.append(&mut undo_msgs);
+                    state.redo.push(edit);
+                }
+            } else if redo {
+                if let Some(edit) = state.redo.pop() {
+                    let mut redo_msgs: Messages = edit
+                        .changes
+                        .iter()
+                        .map(|(name, _, new)| (name.clone(), new.clone()))
+                        .collect();
+                    store_messages(&redo_msgs, &mut state);
+                    messages.append(&mut redo_msgs);
+                    state.undo.push(edit);
+                }
+            }
+
+            // Values set in an element after inserting it can reach the program before the element is sent back, which
+            // would then replace them, so ask for the variable again once they have been set
+            let mut refresh = Vec::new();
             for (name, value) in messages.into_iter() {
+                record_history(&name, time, &mut state);
                 let serialized = value.serialize();
-                println!(
-                    "s{};{}-{}",
-                    &name[1..],
-                    serialized.as_bytes().len(),
-                    serialized
-                );
+                println!("s{};{}-{}", &name[1..], serialized.len(), serialized);
+                let trigger = value
+                    .as_namespaced()
+                    .and_then(|trigger| trigger.name.get(2));
+                if trigger.map(String::as_str) == Some("insert") {
+                    let variable = name[1..].split('.').next().unwrap().to_owned();
+                    if !refresh.contains(&variable) {
+                        refresh.push(variable);
+                    }
+                }
+            }
+            for variable in refresh {
+                println!("g{}", variable);
             }
 
             let (needs_repaint, shapes) = egui.end_frame(&display);
@@ -285,21 +459,157 @@ fn draw_repr(
     namespace: String,
     state: &mut State,
 ) -> Messages {
-    if repr.name[0] == "livemod" {
+    // Only the widgets which edit values are disabled, so that the sections of read-only values can still be expanded
+    if state.disabled
+        && repr.name[0] == "livemod"
+        && !has_section(repr)
+        && repr.name[1] != "variants"
+    {
+        state.disabled = false;
+        let msgs = ui
+            .scope(|ui| {
+                ui.set_enabled(false);
+                draw_repr(ui, repr, namespace, state)
+            })
+            .inner;
+        state.disabled = true;
+        return msgs;
+    }
+    let previous = state
+        .tracked_data
+        .get(&namespace)
+        .map(|value| (namespace.clone(), value.clone()));
+    let msgs = if repr.name[0] == "livemod" {
         match repr.name[1].as_str() {
             "fields" => {
                 let mut msgs = Messages::default();
+                let search = state.search.clone();
                 for (name, field) in &repr.parameters {
-                    let field_namespace = format!("{}.{}", namespace, name);
                     let field = field.as_namespaced().unwrap();
-                    ui.label(name);
-                    msgs.append(&mut draw_repr(ui, field, field_namespace, state));
+                    if field.name == ["livemod", "group"] {
+                        if !search.is_empty() && !contains_search_match(field, &namespace, &search)
+                        {
+                            continue;
+                        }
+                        // Groups don't change the path of their fields
+                        msgs.append(&mut draw_repr(ui, field, namespace.clone(), state));
+                        ui.end_row();
+                        continue;
+                    }
+                    let field_namespace = format!("{}.{}", namespace, name);
+                    let search_hit =
+                        !search.is_empty() && search_matches(&field_namespace, &search);
+                    if !search.is_empty()
+                        && !search_hit
+                        && !contains_search_match(field, &field_namespace, &search)
+                    {
+                        continue;
+                    }
+                    if let Some(condition) = field
+                        .parameters
+                        .get("visible_if")
+                        .and_then(|c| c.as_namespaced())
+                    {
+                        if !condition_holds(condition, &namespace, state) {
+                            continue;
+                        }
+                    }
+                    let readonly = field
+                        .parameters
+                        .get("readonly")
+                        .and_then(|r| r.as_bool())
+                        .copied()
+                        .unwrap_or(false);
+                    let enabled = !readonly
+                        && match field
+                            .parameters
+                            .get("enabled_if")
+                            .and_then(|c| c.as_namespaced())
+                        {
+                            Some(condition) => condition_holds(condition, &namespace, state),
+                            None => true,
+                        };
+                    let modified = !readonly && is_modified(field, &field_namespace, state);
+                    let mut label = egui::Label::new(name);
+                    // Highlight the fields which match the search, but not all of the fields inside them
+                    if search_hit && !search_matches(&namespace, &search) {
+                        label = label.background_color(ui.visuals().selection.bg_fill);
+                    }
+                    if modified {
+                        label = label.strong().text_color(egui::Color32::GOLD);
+                    }
+                    let label = ui.add(label);
+                    if let Some(doc) = field.parameters.get("doc").and_then(|d| d.as_string()) {
+                        label.on_hover_text(doc);
+                    }
+                    let parent_disabled = state.disabled;
+                    state.disabled = parent_disabled || !enabled;
+                    let mut field_msgs = draw_repr(ui, field, field_namespace.clone(), state);
+                    state.disabled = parent_disabled;
+                    let plottable = field.name[0] == "livemod"
+                        && matches!(field.name[1].as_str(), "sint" | "uint" | "float");
+                    if plottable || modified {
+                        ui.horizontal(|ui| {
+                            if plottable {
+                                let plotted = state.plots.contains_key(&field_namespace);
+                                if ui
+                                    .selectable_label(plotted, "Plot")
+                                    .on_hover_text("Show how this value has changed recently")
+                                    .clicked()
+                                {
+                                    if plotted {
+                                        state.plots.remove(&field_namespace);
+                                    } else {
+                                        state
+                                            .plots
+                                            .insert(field_namespace.clone(), VecDeque::new());
+                                        record_history(&field_namespace, ui.input().time, state);
+                                    }
+                                }
+                            }
+                            if modified
+                                && ui
+                                    .small_button("Reset")
+                                    .on_hover_text("Change back to the initial value")
+                                    .clicked()
+                            {
+                                field_msgs.append(&mut reset_value(&field_namespace, state));
+                            }
+                        });
+                    }
+                    // Disabled widgets can't be edited, but read-only values are never sent regardless
+                    if !readonly {
+                        msgs.append(&mut field_msgs);
+                    }
                     ui.end_row();
+                    if let Some(history) = state.plots.get(&field_namespace) {
+                        ui.label("");
+                        draw_plot(ui, &field_namespace, history);
+                        ui.end_row();
+                    }
                 }
                 msgs
             }
-            "struct" => ui
-                .collapsing(repr.parameters["name"].as_string().unwrap(), |ui| {
+            "struct"
+                if repr.parameters["fields"]
+                    .as_namespaced()
+                    .unwrap()
+                    .parameters
+                    .is_empty() =>
+            {
+                ui.label(repr.parameters["name"].as_string().unwrap());
+                vec![]
+            }
+            "struct" => {
+                let name = repr.parameters["name"].as_string().unwrap();
+                search_header(
+                    egui::CollapsingHeader::new(name),
+                    name,
+                    repr,
+                    &namespace,
+                    state,
+                )
+                .show(ui, |ui| {
                     egui::Grid::new(&namespace)
                         .striped(true)
                         .spacing([40.0, 4.0])
@@ -314,9 +624,51 @@ fn draw_repr(
                         .inner
                 })
                 .body_returned
-                .unwrap_or_default(),
-            "enum" => ui
-                .collapsing(repr.parameters["name"].as_string().unwrap(), |ui| {
+                .unwrap_or_default()
+            }
+            "group" => {
+                let name = repr.parameters["name"].as_string().unwrap();
+                let id_source = format!("{}#{}", namespace, name);
+                search_header(
+                    egui::CollapsingHeader::new(name).id_source(&id_source),
+                    &id_source,
+                    repr,
+                    &namespace,
+                    state,
+                )
+                .show(ui, |ui| {
+                    egui::Grid::new(&id_source)
+                        .striped(true)
+                        .spacing([40.0, 4.0])
+                        .show(ui, |ui| {
+                            draw_repr(
+                                ui,
+                                repr.parameters["fields"].as_namespaced().unwrap(),
+                                namespace,
+                                state,
+                            )
+                        })
+                        .inner
+                })
+                .body_returned
+                .unwrap_or_default()
+            }
+            "enum" if !repr.parameters.contains_key("current") => draw_variants(
+                ui,
+                repr.parameters["variants"].as_namespaced().unwrap(),
+                format!("{}.variant", namespace),
+                state,
+            ),
+            "enum" => {
+                let name = repr.parameters["name"].as_string().unwrap();
+                search_header(
+                    egui::CollapsingHeader::new(name),
+                    name,
+                    repr,
+                    &namespace,
+                    state,
+                )
+                .show(ui, |ui| {
                     egui::Grid::new(&namespace)
                         .striped(true)
                         .spacing([40.0, 4.0])
@@ -339,41 +691,22 @@ fn draw_repr(
                         .inner
                 })
                 .body_returned
-                .unwrap_or_default(),
+                .unwrap_or_default()
+            }
             "variants" => {
-                let selected_variant = state
-                    .tracked_data
-                    .entry(namespace.clone())
-                    .or_insert(AnyData::String(String::new()))
-                    .as_string_mut()
-                    .unwrap();
-                let mut changed = false;
-                egui::ComboBox::from_id_source(&namespace)
-                    .selected_text(selected_variant.clone())
-                    .show_ui(ui, |ui| {
-                        for (_i, variant) in &repr.parameters {
-                            let variant = variant.as_string().unwrap();
-                            changed |= ui
-                                .selectable_value(
-                                    selected_variant,
-                                    variant.clone(),
-                                    variant.clone(),
-                                )
-                                .clicked();
-                        }
-                    });
+                let msgs = draw_variants(ui, repr, namespace, state);
                 ui.end_row();
-                if changed {
-                    vec![(
-                        namespace.clone(),
-                        state.tracked_data[&namespace].clone().into(),
-                    )]
-                } else {
-                    vec![]
-                }
+                msgs
             }
             "vec" => {
-                ui.collapsing("Vec", |ui| {
+                search_header(
+                    egui::CollapsingHeader::new("Vec"),
+                    "Vec",
+                    repr,
+                    &namespace,
+                    state,
+                )
+                .show(ui, |ui| {
                     egui::Grid::new(&namespace)
                         .striped(true)
                         .spacing([40.0, 4.0])
@@ -385,17 +718,28 @@ fn draw_repr(
                                     repr.parameters["len"].as_unsigned_int().copied().unwrap(),
                                 ),
                             );
+                            let previous = len.clone();
                             let mut msgs = Messages::default();
+                            let disabled = state.disabled;
                             if ui
-                                .add(
-                                    egui::DragValue::new(len.as_unsigned_int_mut().unwrap())
-                                        .speed(0.1),
-                                )
+                                .scope(|ui| {
+                                    ui.set_enabled(!disabled);
+                                    ui.add(
+                                        egui::DragValue::new(len.as_unsigned_int_mut().unwrap())
+                                            .speed(0.1),
+                                    )
+                                })
+                                .inner
                                 .changed()
                             {
-                                msgs.push((len_field, len.clone().try_into().unwrap()));
+                                msgs.push((len_field.clone(), len.clone().into()));
+                                state.previous.entry(len_field).or_insert(previous);
                             }
                             ui.end_row();
+                            // The rows' labels are used as handles to drag them to a new position
+                            let mut handles = Vec::new();
+                            let mut dragged = None;
+                            let mut released = None;
                             for (i, field) in &repr.parameters {
                                 let i = match i.parse::<usize>() {
                                     Ok(i) => i,
@@ -403,11 +747,91 @@ fn draw_repr(
                                 };
                                 let field_namespace = format!("{}.{}", namespace, i);
                                 let field = field.as_namespaced().unwrap();
-                                ui.label(format!("{}", i));
-                                msgs.append(&mut draw_repr(ui, field, field_namespace, state));
-                                //TODO: Add remove button, insert button, etc.
+                                let handle = if disabled {
+                                    ui.label(format!("{}", i))
+                                } else {
+                                    ui.add(
+                                        egui::Label::new(format!("{}", i))
+                                            .sense(egui::Sense::drag()),
+                                    )
+                                    .on_hover_text("Drag to reorder")
+                                };
+                                if handle.drag_released() {
+                                    released = Some(i);
+                                } else if handle.dragged() {
+                                    dragged = Some(i);
+                                }
+                                handles.push(handle.rect);
+                                msgs.append(&mut draw_repr(
+                                    ui,
+                                    field,
+                                    field_namespace.clone(),
+                                    state,
+                                ));
+                                ui.horizontal(|ui| {
+                                    ui.set_enabled(!disabled);
+                                    if ui
+                                        .small_button("Insert")
+                                        .on_hover_text("Insert an element before this one")
+                                        .clicked()
+                                    {
+                                        msgs.push((
+                                            namespace.clone(),
+                                            vec_trigger("insert", &[("idx", i)]),
+                                        ));
+                                    }
+                                    if ui.small_button("Duplicate").clicked() {
+                                        msgs.push((
+                                            namespace.clone(),
+                                            vec_trigger("insert", &[("idx", i + 1)]),
+                                        ));
+                                        let mut copy_msgs = copy_values(
+                                            field,
+                                            &field_namespace,
+                                            &format!("{}.{}", namespace, i + 1),
+                                            &state.tracked_data,
+                                            &state.tracked_data,
+                                        );
+                                        store_messages(&copy_msgs, state);
+                                        msgs.append(&mut copy_msgs);
+                                    }
+                                    if ui.small_button("Remove").clicked() {
+                                        msgs.push((
+                                            namespace.clone(),
+                                            vec_trigger("rm", &[("idx", i)]),
+                                        ));
+                                    }
+                                });
                                 ui.end_row();
                             }
+                            if let (Some(from), Some(pos)) =
+                                (dragged.or(released), ui.input().pointer.interact_pos())
+                            {
+                                // The position between rows which the element would be moved to
+                                let gap = handles
+                                    .iter()
+                                    .filter(|rect| rect.center().y < pos.y)
+                                    .count();
+                                if released.is_some() {
+                                    let to = if gap > from { gap - 1 } else { gap };
+                                    if to != from {
+                                        msgs.push((
+                                            namespace.clone(),
+                                            vec_trigger("mv", &[("from", from), ("to", to)]),
+                                        ));
+                                    }
+                                } else {
+                                    let y = match handles.get(gap) {
+                                        Some(rect) => rect.top(),
+                                        None => handles[handles.len() - 1].bottom(),
+                                    };
+                                    let rect = ui.min_rect();
+                                    ui.painter().line_segment(
+                                        [egui::pos2(rect.left(), y), egui::pos2(rect.right(), y)],
+                                        ui.visuals().selection.stroke,
+                                    );
+                                }
+                            }
                             msgs
                         })
                         .inner
@@ -416,62 +840,127 @@ fn draw_repr(
                 .unwrap_or_default()
             }
             "map" => {
-                ui.collapsing("Map", |ui| {
+                search_header(
+                    egui::CollapsingHeader::new("Map"),
+                    "Map",
+                    repr,
+                    &namespace,
+                    state,
+                )
+                .show(ui, |ui| {
                     egui::Grid::new(&namespace)
                         .striped(true)
                         .spacing([40.0, 4.0])
                         .show(ui, |ui| {
                             let key_repr = repr.parameters["key"].as_namespaced().unwrap();
                             let mut msgs = Messages::default();
-                            for (key, value) in repr.parameters["keys"]
+
+                            let mut view = state.map_views.remove(&namespace).unwrap_or_default();
+                            ui.label("Filter:");
+                            ui.horizontal(|ui| {
+                                ui.text_edit_singleline(&mut view.filter);
+                                ui.checkbox(&mut view.sorted, "Sort");
+                            });
+                            ui.end_row();
+                            let mut entries = repr.parameters["keys"]
                                 .as_namespaced()
                                 .unwrap()
                                 .parameters
-                                .iter()
-                                .map(|(i, _k)| i)
+                                .keys()
                                 .zip(
                                     repr.parameters["values"]
                                         .as_namespaced()
                                         .unwrap()
                                         .parameters
-                                        .iter()
-                                        .map(|(_i, v)| v.as_namespaced().unwrap()),
+                                        .values()
+                                        .map(|v| v.as_namespaced().unwrap()),
                                 )
-                            {
+                                .map(|(key, value)| {
+                                    let key_data = stored_values(
+                                        &format!("{}.keys.{}", namespace, key),
+                                        &state.tracked_data,
+                                    );
+                                    (key, key_data, value)
+                                })
+                                .collect::<Vec<_>>();
+                            if !view.filter.is_empty() {
+                                let filter = view.filter.to_lowercase();
+                                entries.retain(|(_, key_data, _)| {
+                                    key_data.iter().any(|data| {
+                                        data.to_string().to_lowercase().contains(&filter)
+                                    })
+                                });
+                            }
+                            if view.sorted {
+                                entries.sort_by(|(_, a, _), (_, b, _)| {
+                                    a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal)
+                                });
+                            }
+                            state.map_views.insert(namespace.clone(), view);
+
+                            for (key, _, value) in entries {
                                 let key_namespace = format!("{}.keys.{}", namespace, key);
                                 let value_namespace = format!("{}.values.{}", namespace, key);
-                                let mut key_msgs = draw_repr(ui, key_repr, key_namespace, state);
+                                let mut key_msgs =
+                                    draw_repr(ui, key_repr, key_namespace.clone(), state);
                                 let mut val_msgs = draw_repr(ui, value, value_namespace, state);
                                 // Add value messages first, to allow them to update before the key changes, in case of lag.
                                 msgs.append(&mut val_msgs);
                                 msgs.append(&mut key_msgs);
-                                //TODO: Remove button, etc.
+                                if ui
+                                    .add(
+                                        egui::Button::new("Remove")
+                                            .small()
+                                            .enabled(!state.disabled),
+                                    )
+                                    .clicked()
+                                {
+                                    let key = construct_value(
+                                        key_repr,
+                                        key_namespace,
+                                        &state.tracked_data,
+                                    );
+                                    msgs.push((namespace.clone(), map_trigger("rm", key)));
+                                }
                                 ui.end_row();
                             }
                             ui.separator();
                             ui.end_row();
                             ui.label("Insert:");
+                            state.drafts.insert(format!("{}.insert", namespace));
                             draw_repr(ui, key_repr, format!("{}.insert", namespace), state);
-                            if ui.small_button("+").clicked() {
-                                msgs.push((
-                                    namespace.to_string(),
-                                    Parameter::Namespaced(Namespaced::new(
-                                        vec![
-                                            "livemod".to_owned(),
-                                            "map".to_owned(),
-                                            "insert".to_owned(),
-                                        ],
-                                        std::iter::once((
-                                            "key".to_owned(),
-                                            construct_value(
-                                                key_repr,
-                                                format!("{}.insert", namespace),
-                                                state,
-                                            ),
-                                        ))
-                                        .collect(),
-                                    )),
-                                ));
+                            let key = construct_value(
+                                key_repr,
+                                format!("{}.insert", namespace),
+                                &state.tracked_data,
+                            );
+                            let serialized = key.serialize();
+                            let exists = repr.parameters["keys"]
+                                .as_namespaced()
+                                .unwrap()
+                                .parameters
+                                .keys()
+                                .any(|existing| {
+                                    construct_value(
+                                        key_repr,
+                                        format!("{}.keys.{}", namespace, existing),
+                                        &state.tracked_data,
+                                    )
+                                    .serialize()
+                                        == serialized
+                                });
+                            let insert = ui
+                                .add(
+                                    egui::Button::new("+")
+                                        .small()
+                                        .enabled(!exists && !state.disabled),
+                                )
+                                .clicked();
+                            if insert {
+                                msgs.push((namespace.to_string(), map_trigger("insert", key)));
+                            }
+                            if exists {
+                                ui.colored_label(egui::Color32::RED, "Key already exists");
                             }
                             ui.end_row();
                             msgs
@@ -481,26 +970,206 @@ fn draw_repr(
                 .body_returned
                 .unwrap_or_default()
             }
+            "range" => {
+                let inclusive = repr.parameters["inclusive"].as_bool().copied().unwrap();
+                let bounds = repr.parameters["bounds"].as_namespaced().unwrap();
+                let start_namespace = format!("{}.start", namespace);
+                let end_namespace = format!("{}.end", namespace);
+                let mut msgs = ui
+                    .horizontal(|ui| {
+                        let mut msgs = draw_repr(ui, bounds, start_namespace.clone(), state);
+                        ui.label(if inclusive { "..=" } else { ".." });
+                        msgs.append(&mut draw_repr(ui, bounds, end_namespace.clone(), state));
+                        msgs
+                    })
+                    .inner;
+                // Stop whichever bound was moved at the other one, so that `start <= end`
+                for (name, value) in &mut msgs {
+                    let (other_namespace, reversed) = if *name == start_namespace {
+                        (&end_namespace, std::cmp::Ordering::Greater)
+                    } else if *name == end_namespace {
+                        (&start_namespace, std::cmp::Ordering::Less)
+                    } else {
+                        continue;
+                    };
+                    let other = state.tracked_data[other_namespace].clone();
+                    if state.tracked_data[name.as_str()].partial_cmp(&other) == Some(reversed) {
+                        *value = other.clone().into();
+                        state.tracked_data.insert(name.clone(), other);
+                    }
+                }
+                msgs
+            }
+            "color" => {
+                let alpha = repr.parameters["alpha"].as_bool().copied().unwrap();
+                let linear = repr.parameters["linear"].as_bool().copied().unwrap();
+                let storage = repr.parameters["storage"].as_string().unwrap();
+                let components = if alpha { 4 } else { 3 };
+
+                // Normalise the stored components to 0.0..=1.0, in whichever colour space they are stored in
+                let mut rgba = [0.0, 0.0, 0.0, 1.0];
+                if storage == "packed" {
+                    let packed = state
+                        .tracked_data
+                        .entry(namespace.clone())
+                        .or_insert(AnyData::UnsignedInt(0))
+                        .as_unsigned_int()
+                        .copied()
+                        .unwrap();
+                    for (i, component) in rgba.iter_mut().take(components).enumerate() {
+                        *component = ((packed >> (8 * (components - 1 - i))) & 0xFF) as f32 / 255.0;
+                    }
+                } else {
+                    for (i, component) in rgba.iter_mut().take(components).enumerate() {
+                        *component = match state.tracked_data.get(&format!("{}.{}", namespace, i)) {
+                            Some(AnyData::Float(v)) => *v as f32,
+                            Some(AnyData::UnsignedInt(v)) => *v as f32 / 255.0,
+                            _ => 0.0,
+                        };
+                    }
+                }
+
+                let changed = if linear {
+                    if alpha {
+                        ui.color_edit_button_rgba_unmultiplied(&mut rgba).changed()
+                    } else {
+                        let mut rgb = [rgba[0], rgba[1], rgba[2]];
+                        let changed = ui.color_edit_button_rgb(&mut rgb).changed();
+                        rgba[..3].copy_from_slice(&rgb);
+                        changed
+                    }
+                } else {
+                    let mut srgba = rgba.map(|c| (c * 255.0).round() as u8);
+                    let changed = if alpha {
+                        ui.color_edit_button_srgba_unmultiplied(&mut srgba)
+                            .changed()
+                    } else {
+                        let mut srgb = [srgba[0], srgba[1], srgba[2]];
+                        let changed = ui.color_edit_button_srgb(&mut srgb).changed();
+                        srgba[..3].copy_from_slice(&srgb);
+                        changed
+                    };
+                    rgba = srgba.map(|c| c as f32 / 255.0);
+                    changed
+                };
+
+                if !changed {
+                    vec![]
+                } else if storage == "packed" {
+                    let packed = rgba
+                        .iter()
+                        .take(components)
+                        .fold(0, |packed, c| (packed << 8) | (c * 255.0).round() as u64);
+                    let value = AnyData::UnsignedInt(packed);
+                    state.tracked_data.insert(namespace.clone(), value.clone());
+                    vec![(namespace, value.into())]
+                } else {
+                    rgba.iter()
+                        .take(components)
+                        .enumerate()
+                        .map(|(i, c)| {
+                            let value = if storage == "byte" {
+                                AnyData::UnsignedInt((c * 255.0).round() as u64)
+                            } else {
+                                AnyData::Float(*c as f64)
+                            };
+                            let field_namespace = format!("{}.{}", namespace, i);
+                            if let Some(previous) = state
+                                .tracked_data
+                                .insert(field_namespace.clone(), value.clone())
+                            {
+                                state
+                                    .previous
+                                    .entry(field_namespace.clone())
+                                    .or_insert(previous);
+                            }
+                            (field_namespace, value.into())
+                        })
+                        .collect()
+                }
+            }
+            "angle" => {
+                let wrap_min = repr
+                    .parameters
+                    .get("wrap_min")
+                    .and_then(|p| p.as_float().copied());
+                let wrap_max = repr
+                    .parameters
+                    .get("wrap_max")
+                    .and_then(|p| p.as_float().copied());
+
+                let value = state
+                    .tracked_data
+                    .entry(namespace.clone())
+                    .or_insert(AnyData::Float(0.0));
+                // The value is stored in radians, but edited in degrees
+                if ui
+                    .add(
+                        egui::DragValue::from_get_set(|val| match val {
+                            Some(degrees) => {
+                                let mut radians = degrees.to_radians();
+                                if let (Some(min), Some(max)) = (wrap_min, wrap_max) {
+                                    if min < max {
+                                        radians = min + (radians - min).rem_euclid(max - min);
+                                    }
+                                }
+                                *value = AnyData::Float(radians);
+                                radians.to_degrees()
+                            }
+                            None => value.as_float().copied().unwrap().to_degrees(),
+                        })
+                        .speed(1.0)
+                        .suffix("°"),
+                    )
+                    .changed()
+                {
+                    vec![(namespace, value.clone().into())]
+                } else {
+                    vec![]
+                }
+            }
+            "vector" => {
+                ui.horizontal(|ui| {
+                    let mut msgs = Messages::default();
+                    for (name, component) in &repr.parameters {
+                        // Skip any parameters which aren't components, such as `doc`
+                        let component = match component.as_namespaced() {
+                            Some(component) => component,
+                            None => continue,
+                        };
+                        ui.label(name);
+                        msgs.append(&mut draw_repr(
+                            ui,
+                            component,
+                            format!("{}.{}", namespace, name),
+                            state,
+                        ));
+                    }
+                    msgs
+                })
+                .inner
+            }
             "bool" => {
                 let value = state
                     .tracked_data
                     .entry(namespace.clone())
                     .or_insert(AnyData::Bool(false));
                 if ui.checkbox(value.as_bool_mut().unwrap(), "").changed() {
-                    vec![(namespace, value.clone().try_into().unwrap())]
+                    vec![(namespace, value.clone().into())]
                 } else {
                     vec![]
                 }
             }
-            "trigger" => ui
-                .button(
-                    repr.parameters
-                        .get("name")
-                        .and_then(|param| param.as_string().map(|s| s.as_str()))
-                        .unwrap_or("Call"),
-                )
-                .clicked()
-                .then(|| {
+            "trigger" => {
+                if ui
+                    .button(
+                        repr.parameters
+                            .get("name")
+                            .and_then(|param| param.as_string().map(|s| s.as_str()))
+                            .unwrap_or("Call"),
+                    )
+                    .clicked()
+                {
                     vec![(
                         namespace,
                         Parameter::Namespaced(Namespaced::new(
@@ -508,8 +1177,10 @@ fn draw_repr(
                             std::iter::empty().collect(),
                         )),
                     )]
-                })
-                .unwrap_or_default(),
+                } else {
+                    vec![]
+                }
+            }
             "string" => {
                 let value = state
                     .tracked_data
@@ -527,7 +1198,7 @@ fn draw_repr(
                 }
                 .changed()
                 {
-                    vec![(namespace, value.clone().try_into().unwrap())]
+                    vec![(namespace, value.clone().into())]
                 } else {
                     vec![]
                 }
@@ -543,38 +1214,43 @@ fn draw_repr(
                     .parameters
                     .get("suggested_max")
                     .and_then(|p| p.as_signed_int().copied());
+                let style = NumberStyle::from_repr(repr);
 
                 let value = state
                     .tracked_data
                     .entry(namespace.clone())
                     .or_insert(AnyData::SignedInt(0));
+                let get_set = |val: Option<f64>| match val {
+                    Some(val) => {
+                        //TODO: Clamp *before* casting?
+                        let new_val = (snap(val, &style) as i64).clamp(min, max);
+                        *value = AnyData::SignedInt(new_val);
+                        new_val as f64
+                    }
+                    None => value.as_signed_int().copied().unwrap() as f64,
+                };
                 if if let (Some(suggested_min), Some(suggested_max)) =
                     (suggested_min, suggested_max)
                 {
                     ui.add(
-                        egui::Slider::from_get_set(
-                            suggested_min as f64..=suggested_max as f64,
-                            |val| match val {
-                                Some(val) => {
-                                    //TODO: Clamp *before* casting?
-                                    let new_val = (val as i64).clamp(min, max);
-                                    *value = AnyData::SignedInt(new_val);
-                                    new_val as f64
-                                }
-                                None => value.as_signed_int().copied().unwrap() as f64,
-                            } as f64,
+                        style_slider(
+                            egui::Slider::from_get_set(
+                                suggested_min as f64..=suggested_max as f64,
+                                get_set,
+                            ),
+                            &style,
                         )
                         .integer(),
                     )
                 } else {
                     ui.add(
-                        egui::DragValue::new(value.as_signed_int_mut().unwrap())
-                            .clamp_range(min..=max),
+                        style_drag_value(egui::DragValue::from_get_set(get_set), &style)
+                            .max_decimals(0),
                     )
                 }
                 .changed()
                 {
-                    vec![(namespace, value.clone().try_into().unwrap())]
+                    vec![(namespace, value.clone().into())]
                 } else {
                     vec![]
                 }
@@ -590,37 +1266,42 @@ fn draw_repr(
                     .parameters
                     .get("suggested_max")
                     .and_then(|p| p.as_unsigned_int().copied());
+                let style = NumberStyle::from_repr(repr);
 
                 let value = state
                     .tracked_data
                     .entry(namespace.clone())
                     .or_insert(AnyData::UnsignedInt(0));
+                let get_set = |val: Option<f64>| match val {
+                    Some(val) => {
+                        let new_val = (snap(val, &style) as u64).clamp(min, max);
+                        *value = AnyData::UnsignedInt(new_val);
+                        new_val as f64
+                    }
+                    None => value.as_unsigned_int().copied().unwrap() as f64,
+                };
                 if if let (Some(suggested_min), Some(suggested_max)) =
                     (suggested_min, suggested_max)
                 {
                     ui.add(
-                        egui::Slider::from_get_set(
-                            suggested_min as f64..=suggested_max as f64,
-                            |val| match val {
-                                Some(val) => {
-                                    let new_val = (val as u64).clamp(min, max);
-                                    *value = AnyData::UnsignedInt(new_val);
-                                    new_val as f64
-                                }
-                                None => value.as_unsigned_int().copied().unwrap() as f64,
-                            },
+                        style_slider(
+                            egui::Slider::from_get_set(
+                                suggested_min as f64..=suggested_max as f64,
+                                get_set,
+                            ),
+                            &style,
                         )
                         .integer(),
                     )
                 } else {
                     ui.add(
-                        egui::DragValue::new(value.as_unsigned_int_mut().unwrap())
-                            .clamp_range(min..=max),
+                        style_drag_value(egui::DragValue::from_get_set(get_set), &style)
+                            .max_decimals(0),
                     )
                 }
                 .changed()
                 {
-                    vec![(namespace, value.clone().try_into().unwrap())]
+                    vec![(namespace, value.clone().into())]
                 } else {
                     vec![]
                 }
@@ -636,33 +1317,36 @@ fn draw_repr(
                     .parameters
                     .get("suggested_max")
                     .and_then(|p| p.as_float().copied());
+                let style = NumberStyle::from_repr(repr);
 
                 let value = state
                     .tracked_data
                     .entry(namespace.clone())
                     .or_insert(AnyData::Float(0.0));
+                let get_set = |val: Option<f64>| match val {
+                    Some(val) => {
+                        let new_val = snap(val, &style).clamp(min, max);
+                        *value = AnyData::Float(new_val);
+                        new_val
+                    }
+                    None => value.as_float().copied().unwrap(),
+                };
                 if if let (Some(suggested_min), Some(suggested_max)) =
                     (suggested_min, suggested_max)
                 {
-                    ui.add(egui::Slider::from_get_set(
-                        suggested_min..=suggested_max,
-                        |val| match val {
-                            Some(val) => {
-                                let new_val = val.clamp(min, max);
-                                *value = AnyData::Float(new_val);
-                                new_val
-                            }
-                            None => value.as_float().copied().unwrap(),
-                        },
+                    ui.add(style_slider(
+                        egui::Slider::from_get_set(suggested_min..=suggested_max, get_set),
+                        &style,
                     ))
                 } else {
-                    ui.add(
-                        egui::DragValue::new(value.as_float_mut().unwrap()).clamp_range(min..=max),
-                    )
+                    ui.add(style_drag_value(
+                        egui::DragValue::from_get_set(get_set),
+                        &style,
+                    ))
                 }
                 .changed()
                 {
-                    vec![(namespace, value.clone().try_into().unwrap())]
+                    vec![(namespace, value.clone().into())]
                 } else {
                     vec![]
                 }
@@ -671,68 +1355,781 @@ fn draw_repr(
         }
     } else {
         todo!()
+    };
+    remember_previous(previous, &msgs, state);
+    msgs
+}
+
+/// Whether `repr` is drawn as a section which can be expanded to show the values inside it, or is the list of fields
+/// inside one. Variants are drawn as a row of their own, and disable their dropdown themselves.
+fn has_section(repr: &Namespaced<Repr>) -> bool {
+    match repr.name[1].as_str() {
+        "fields" | "group" | "vec" | "map" => true,
+        "struct" => !repr.parameters["fields"]
+            .as_namespaced()
+            .unwrap()
+            .parameters
+            .is_empty(),
+        "enum" => repr.parameters.contains_key("current"),
+        _ => false,
     }
 }
 
-fn construct_value(
+/// Draw a dropdown to select between the variants in the given `livemod:variants` repr.
+fn draw_variants(
+    ui: &mut egui::Ui,
     repr: &Namespaced<Repr>,
     namespace: String,
     state: &mut State,
+) -> Messages {
+    let previous = state
+        .tracked_data
+        .get(&namespace)
+        .map(|value| (namespace.clone(), value.clone()));
+    let selected_variant = state
+        .tracked_data
+        .entry(namespace.clone())
+        .or_insert_with(|| AnyData::String(first_variant(repr)))
+        .as_string_mut()
+        .unwrap();
+    let mut changed = false;
+    let disabled = state.disabled;
+    ui.scope(|ui| {
+        ui.set_enabled(!disabled);
+        egui::ComboBox::from_id_source(&namespace)
+            .selected_text(selected_variant.clone())
+            .show_ui(ui, |ui| {
+                for variant in repr.parameters.values() {
+                    let variant = variant.as_string().unwrap();
+                    changed |= ui
+                        .selectable_value(selected_variant, variant.clone(), variant.clone())
+                        .clicked();
+                }
+            });
+    });
+    let msgs = if changed {
+        vec![(
+            namespace.clone(),
+            state.tracked_data[&namespace].clone().into(),
+        )]
+    } else {
+        vec![]
+    };
+    remember_previous(previous, &msgs, state);
+    msgs
+}
+
+/// Remember the value at a path from before it was drawn, if `msgs` changes it, to be able to undo the change.
+fn remember_previous(
+    previous: Option<(String, AnyData)>,
+    msgs: &[(String, Parameter<Value>)],
+    state: &mut State,
+) {
+    if let Some((path, value)) = previous {
+        if msgs.iter().any(|(name, _)| *name == path) {
+            state.previous.entry(path).or_insert(value);
+        }
+    }
+}
+
+/// Round `val` to the nearest multiple of the style's step, if it has one.
+fn snap(val: f64, style: &NumberStyle) -> f64 {
+    match style.step {
+        Some(step) if step > 0.0 => (val / step).round() * step,
+        _ => val,
+    }
+}
+
+/// Apply the display options of a numeric builtin to a slider.
+fn style_slider<'a>(slider: egui::Slider<'a>, style: &NumberStyle) -> egui::Slider<'a> {
+    let mut slider = slider.logarithmic(style.logarithmic);
+    if let Some(precision) = style.precision {
+        slider = slider.fixed_decimals(precision as usize);
+    }
+    if let Some(unit) = &style.unit {
+        slider = slider.suffix(unit);
+    }
+    slider
+}
+
+/// Apply the display options of a numeric builtin to a drag value.
+fn style_drag_value<'a>(
+    drag_value: egui::DragValue<'a>,
+    style: &NumberStyle,
+) -> egui::DragValue<'a> {
+    let mut drag_value = drag_value;
+    if let Some(step) = style.step {
+        drag_value = drag_value.speed(step);
+    }
+    if let Some(precision) = style.precision {
+        drag_value = drag_value.fixed_decimals(precision as usize);
+    }
+    if let Some(unit) = &style.unit {
+        drag_value = drag_value.suffix(unit);
+    }
+    drag_value
+}
+
+fn construct_value(
+    repr: &Namespaced<Repr>,
+    namespace: String,
+    data: &HashMap<String, AnyData>,
 ) -> Parameter<Value> {
     if repr.name[0] == "livemod" {
         match repr.name[1].as_str() {
-            "bool" => {
-                let value = state
-                    .tracked_data
-                    .entry(namespace)
-                    .or_insert(AnyData::Bool(false));
-                Parameter::Bool(*value.as_bool().unwrap())
+            "bool" => Parameter::Bool(
+                data.get(&namespace)
+                    .and_then(|v| v.as_bool())
+                    .copied()
+                    .unwrap_or(false),
+            ),
+            "sint" => Parameter::SignedInt(
+                data.get(&namespace)
+                    .and_then(|v| v.as_signed_int())
+                    .copied()
+                    .unwrap_or(0),
+            ),
+            "uint" => Parameter::UnsignedInt(
+                data.get(&namespace)
+                    .and_then(|v| v.as_unsigned_int())
+                    .copied()
+                    .unwrap_or(0),
+            ),
+            "float" => Parameter::Float(
+                data.get(&namespace)
+                    .and_then(|v| v.as_float())
+                    .copied()
+                    .unwrap_or(0.0),
+            ),
+            "string" => Parameter::String(
+                data.get(&namespace)
+                    .and_then(|v| v.as_string())
+                    .cloned()
+                    .unwrap_or_default(),
+            ),
+            "struct" => Parameter::Namespaced(Namespaced::new(
+                vec!["livemod".to_owned(), "struct".to_owned()],
+                construct_fields(
+                    repr.parameters["fields"].as_namespaced().unwrap(),
+                    namespace,
+                    data,
+                ),
+            )),
+            "enum" => {
+                let variants = repr.parameters["variants"].as_namespaced().unwrap();
+                let variant = data
+                    .get(&format!("{}.variant", namespace))
+                    .and_then(|v| v.as_string())
+                    .cloned()
+                    .unwrap_or_else(|| first_variant(variants));
+                let mut parameters = LinkedHashMap::new();
+                parameters.insert("variant".to_owned(), Parameter::String(variant));
+                if let Some(current) = repr.parameters.get("current") {
+                    parameters.insert(
+                        "current".to_owned(),
+                        Parameter::Namespaced(Namespaced::new(
+                            vec!["livemod".to_owned(), "fields".to_owned()],
+                            construct_fields(
+                                current.as_namespaced().unwrap(),
+                                format!("{}.current", namespace),
+                                data,
+                            ),
+                        )),
+                    );
+                }
+                Parameter::Namespaced(Namespaced::new(
+                    vec!["livemod".to_owned(), "enum".to_owned()],
+                    parameters,
+                ))
             }
-            "sint" => {
-                let value = state
-                    .tracked_data
-                    .entry(namespace)
-                    .or_insert(AnyData::SignedInt(0));
-                Parameter::SignedInt(*value.as_signed_int().unwrap())
+            name => panic!("Unknown livemod builtin: {}", name),
+        }
+    } else {
+        todo!()
+    }
+}
+
+/// Check whether the path of a value matches a search query, by containing all of its characters in order, ignoring
+/// case.
+fn search_matches(path: &str, query: &str) -> bool {
+    let mut path = path.chars().flat_map(char::to_lowercase);
+    query
+        .chars()
+        .flat_map(char::to_lowercase)
+        .all(|q| path.any(|c| c == q))
+}
+
+/// Check whether any value inside the value at `namespace` has a path matching a search query.
+fn contains_search_match(repr: &Namespaced<Repr>, namespace: &str, query: &str) -> bool {
+    let mut found = false;
+    for_each_child(repr, namespace, &mut |child, child_namespace| {
+        found = found
+            || search_matches(&child_namespace, query)
+            || contains_search_match(child, &child_namespace, query);
+    });
+    found
+}
+
+/// Start a collapsing header open while searching, if the value it contains has a search result.
+///
+/// The header's state can't be changed once it has been shown, so it is given a new ID for each search instead.
+fn search_header(
+    header: egui::CollapsingHeader,
+    id_source: &str,
+    repr: &Namespaced<Repr>,
+    namespace: &str,
+    state: &State,
+) -> egui::CollapsingHeader {
+    if !state.search.is_empty() && contains_search_match(repr, namespace, &state.search) {
+        header
+            .id_source((id_source, &state.search))
+            .default_open(true)
+    } else {
+        header
+    }
+}
+
+/// Create a trigger to send to a vector, such as `livemod:vec:rm`, with the given index parameters.
+fn vec_trigger(action: &str, indices: &[(&str, usize)]) -> Parameter<Value> {
+    Parameter::Namespaced(Namespaced::new(
+        vec!["livemod".to_owned(), "vec".to_owned(), action.to_owned()],
+        indices
+            .iter()
+            .map(|(name, index)| (name.to_string(), Parameter::UnsignedInt(*index as u64)))
+            .collect(),
+    ))
+}
+
+/// Create a trigger to send to a map, such as `livemod:map:rm`, for the entry with the given key.
+fn map_trigger(action: &str, key: Parameter<Value>) -> Parameter<Value> {
+    Parameter::Namespaced(Namespaced::new(
+        vec!["livemod".to_owned(), "map".to_owned(), action.to_owned()],
+        std::iter::once(("key".to_owned(), key)).collect(),
+    ))
+}
+
+/// Get the values stored for `namespace` and the values inside it, in order of their names.
+fn stored_values(namespace: &str, data: &HashMap<String, AnyData>) -> Vec<AnyData> {
+    let mut values = data
+        .iter()
+        .filter(|(name, _)| is_inside(name, namespace))
+        .collect::<Vec<_>>();
+    values.sort_by_key(|(name, _)| *name);
+    values.into_iter().map(|(_, data)| data.clone()).collect()
+}
+
+/// Create the messages to give the value at `to` the same contents as the value at `from`, which both have the
+/// representation `repr`. The values at `from` are read from `source`, and the values already at `to` from `target`.
+///
+/// Values which decide the structure of others, such as the variant of an enum or the length of a vector, are set
+/// before the values inside them.
+fn copy_values(
+    repr: &Namespaced<Repr>,
+    from: &str,
+    to: &str,
+    source: &HashMap<String, AnyData>,
+    target: &HashMap<String, AnyData>,
+) -> Messages {
+    let mut msgs = Messages::default();
+    if repr.name[0] == "livemod" {
+        match repr.name[1].as_str() {
+            "fields" => {
+                for (name, field) in &repr.parameters {
+                    let field = field.as_namespaced().unwrap();
+                    if field.name == ["livemod", "group"] {
+                        msgs.append(&mut copy_values(field, from, to, source, target));
+                    } else if field.parameters.get("readonly").and_then(|r| r.as_bool())
+                        != Some(&true)
+                    {
+                        msgs.append(&mut copy_values(
+                            field,
+                            &format!("{}.{}", from, name),
+                            &format!("{}.{}", to, name),
+                            source,
+                            target,
+                        ));
+                    }
+                }
+                return msgs;
             }
-            "uint" => {
-                let value = state
-                    .tracked_data
-                    .entry(namespace)
-                    .or_insert(AnyData::UnsignedInt(0));
-                Parameter::UnsignedInt(*value.as_unsigned_int().unwrap())
+            "struct" | "group" => {
+                return copy_values(
+                    repr.parameters["fields"].as_namespaced().unwrap(),
+                    from,
+                    to,
+                    source,
+                    target,
+                );
             }
-            "float" => {
-                let value = state
-                    .tracked_data
-                    .entry(namespace)
-                    .or_insert(AnyData::Float(0.0));
-                Parameter::Float(*value.as_float().unwrap())
+            "enum" => {
+                msgs.append(&mut copy_values(
+                    repr.parameters["variants"].as_namespaced().unwrap(),
+                    &format!("{}.variant", from),
+                    &format!("{}.variant", to),
+                    source,
+                    target,
+                ));
+                if let Some(current) = repr.parameters.get("current") {
+                    msgs.append(&mut copy_values(
+                        current.as_namespaced().unwrap(),
+                        &format!("{}.current", from),
+                        &format!("{}.current", to),
+                        source,
+                        target,
+                    ));
+                }
+                return msgs;
             }
-            "string" => {
-                let value = state
-                    .tracked_data
-                    .entry(namespace)
-                    .or_insert(AnyData::String("".to_string()));
-                Parameter::String(value.as_string().unwrap().to_string())
+            "vec" => {
+                msgs.push((
+                    format!("{}.len", to),
+                    Parameter::UnsignedInt(*repr.parameters["len"].as_unsigned_int().unwrap()),
+                ));
+                for (i, element) in &repr.parameters {
+                    if i.parse::<usize>().is_ok() {
+                        msgs.append(&mut copy_values(
+                            element.as_namespaced().unwrap(),
+                            &format!("{}.{}", from, i),
+                            &format!("{}.{}", to, i),
+                            source,
+                            target,
+                        ));
+                    }
+                }
+                return msgs;
             }
-            name => panic!("Unknown livemod builtin: {}", name),
+            "map" => {
+                let key_repr = repr.parameters["key"].as_namespaced().unwrap();
+                let values = repr.parameters["values"].as_namespaced().unwrap();
+                // Key names don't contain `.`, so the name of each key in the target can be found from its values
+                let keys_prefix = format!("{}.keys.", to);
+                let existing = target
+                    .keys()
+                    .filter_map(|name| name.strip_prefix(&keys_prefix))
+                    .map(|rest| rest.split('.').next().unwrap())
+                    .collect::<std::collections::HashSet<_>>();
+                for key in &existing {
+                    if !values.parameters.contains_key(*key) {
+                        let key_value =
+                            construct_value(key_repr, format!("{}{}", keys_prefix, key), target);
+                        msgs.push((to.to_owned(), map_trigger("rm", key_value)));
+                    }
+                }
+                for (key, value) in &values.parameters {
+                    if !existing.contains(key.as_str()) {
+                        let key_value =
+                            construct_value(key_repr, format!("{}.keys.{}", from, key), source);
+                        msgs.push((to.to_owned(), map_trigger("insert", key_value)));
+                    }
+                    msgs.append(&mut copy_values(
+                        value.as_namespaced().unwrap(),
+                        &format!("{}.values.{}", from, key),
+                        &format!("{}.values.{}", to, key),
+                        source,
+                        target,
+                    ));
+                }
+                return msgs;
+            }
+            _ => {}
         }
-    } else {
-        todo!()
+    }
+    // Any other value is stored as one or more values under its namespace
+    for (name, data) in source {
+        if is_inside(name, from) {
+            msgs.push((
+                format!("{}{}", to, &name[from.len()..]),
+                data.clone().into(),
+            ));
+        }
+    }
+    msgs
+}
+
+/// Create the messages to change the value at `namespace` back to its initial value.
+fn reset_value(namespace: &str, state: &mut State) -> Messages {
+    let initial_repr = state
+        .initial_vars
+        .iter()
+        .map(|(name, repr)| (format!(".{}", name), repr))
+        .find(|(var_namespace, _)| is_inside(namespace, var_namespace))
+        .and_then(|(var_namespace, repr)| find_repr(repr, &var_namespace, namespace))
+        .cloned();
+    let msgs = match initial_repr {
+        Some(repr) => copy_values(
+            &repr,
+            namespace,
+            namespace,
+            &state.initial_data,
+            &state.tracked_data,
+        ),
+        None => Messages::default(),
+    };
+    store_messages(&msgs, state);
+    msgs
+}
+
+/// Add the values changed by `msgs` to the undo history, merging them into the last change if they continue it.
+///
+/// Triggers, and values which weren't stored before being changed, can't be undone and aren't recorded.
+fn record_edit(
+    msgs: &[(String, Parameter<Value>)],
+    time: f64,
+    pointer_down: bool,
+    state: &mut State,
+) {
+    let changes = msgs
+        .iter()
+        .filter(|(_, value)| value.as_namespaced().is_none())
+        .filter_map(|(name, value)| {
+            let old = state.previous.get(name)?.clone().into();
+            Some((name.clone(), old, value.clone()))
+        })
+        .collect::<Vec<_>>();
+    if changes.is_empty() {
+        return;
+    }
+    state.redo.clear();
+    if let Some(last) = state.undo.last_mut() {
+        // Continue the last change if the same values are being changed, while dragging or typing
+        let same_values = last.changes.len() == changes.len()
+            && last
+                .changes
+                .iter()
+                .zip(&changes)
+                .all(|((a, _, _), (b, _, _))| a == b);
+        // Other values are changed by the pointer, so are only merged until it is released
+        let typing = changes
+            .iter()
+            .all(|(_, _, value)| value.as_string().is_some());
+        let continuing =
+            (last.pointer_down && pointer_down) || (typing && time - last.time < UNDO_MERGE_TIME);
+        if same_values && continuing {
+            for ((_, _, new), (_, _, value)) in last.changes.iter_mut().zip(changes) {
+                *new = value;
+            }
+            last.time = time;
+            last.pointer_down = pointer_down;
+            return;
+        }
+    }
+    state.undo.push(Edit {
+        changes,
+        time,
+        pointer_down,
+    });
+    if state.undo.len() > UNDO_LIMIT {
+        state.undo.remove(0);
+    }
+}
+
+/// Store the values being sent to the program, so they are shown straight away, even if the program doesn't send them
+/// back.
+fn store_messages(msgs: &[(String, Parameter<Value>)], state: &mut State) {
+    for (name, value) in msgs {
+        // Triggers aren't stored
+        if value.as_namespaced().is_none() {
+            if let Some(previous) = state.tracked_data.get(name) {
+                state
+                    .previous
+                    .entry(name.clone())
+                    .or_insert_with(|| previous.clone());
+            }
+            recursive_insert(name.clone(), value.clone(), &mut state.tracked_data);
+        }
+    }
+}
+
+/// Add the current value of each plotted value inside `namespace` to its history, and forget values too old to be
+/// shown.
+fn record_history(namespace: &str, time: f64, state: &mut State) {
+    for (name, history) in &mut state.plots {
+        if !is_inside(name, namespace) {
+            continue;
+        }
+        if let Some(value) = state.tracked_data.get(name).and_then(AnyData::to_f64) {
+            history.push_back((time, value));
+        }
+        // Keep the last value from before the plotted time, so the line reaches the edge of the plot
+        while history.len() > 1 && history[1].0 < time - PLOT_TIME {
+            history.pop_front();
+        }
+    }
+}
+
+/// Draw the history of a value, with the current time at zero.
+///
+/// The history only changes when the value does, so the last value is drawn up to the current time.
+fn draw_plot(ui: &mut egui::Ui, namespace: &str, history: &VecDeque<(f64, f64)>) {
+    use egui::widgets::plot::{Line, Plot, Value, Values};
+
+    let now = ui.input().time;
+    // Skip values from before the plotted time, except the last one, which the line starts from
+    let start = history
+        .iter()
+        .rposition(|(time, _)| *time < now - PLOT_TIME)
+        .unwrap_or(0);
+    let current = history.back().map(|(_, value)| (now, *value));
+    let line = Line::new(Values::from_values_iter(
+        history
+            .iter()
+            .skip(start)
+            .copied()
+            .chain(current)
+            .map(|(time, value)| Value::new(time - now, value)),
+    ));
+    ui.add(
+        Plot::new(namespace)
+            .line(line)
+            .height(100.0)
+            .include_x(-PLOT_TIME)
+            .include_x(0.0)
+            .allow_drag(false)
+            .allow_zoom(false),
+    );
+}
+
+/// Whether the value at `namespace`, or any value inside it which can be edited, is different to its initial value.
+fn is_modified(repr: &Namespaced<Repr>, namespace: &str, state: &State) -> bool {
+    let differs = |path: &str| state.modified.contains(path);
+    // Containers are compared by the values which decide their structure, and then by the values inside them
+    let structure_modified = match (repr.name[0].as_str(), repr.name[1].as_str()) {
+        ("livemod", "fields") | ("livemod", "struct") | ("livemod", "group") => false,
+        ("livemod", "enum") => differs(&format!("{}.variant", namespace)),
+        ("livemod", "vec") => differs(&format!("{}.len", namespace)),
+        ("livemod", "map") => differs(&format!("{}.keys", namespace)),
+        _ => return differs(namespace),
+    };
+    let mut modified = structure_modified;
+    for_each_child(repr, namespace, &mut |child, child_namespace| {
+        let readonly = child.parameters.get("readonly").and_then(|r| r.as_bool()) == Some(&true);
+        modified = modified || (!readonly && is_modified(child, &child_namespace, state));
+    });
+    modified
+}
+
+/// Find the paths of the values which are different to their initial values, or which have been added or removed, and
+/// the namespaces containing them.
+fn find_modified(state: &State) -> HashSet<String> {
+    let added = state
+        .tracked_data
+        .keys()
+        .filter(|name| !state.initial_data.contains_key(*name) && !is_draft(name, &state.drafts));
+    let changed = state
+        .initial_data
+        .iter()
+        .filter(|(name, value)| state.tracked_data.get(*name) != Some(value))
+        .map(|(name, _)| name);
+    let mut modified = HashSet::new();
+    for name in added.chain(changed) {
+        let mut path = name.as_str();
+        while !path.is_empty() && modified.insert(path.to_owned()) {
+            path = &path[..path.rfind('.').unwrap_or(0)];
+        }
+    }
+    modified
+}
+
+/// Whether `path` is `namespace` itself, or the path of a value inside it.
+fn is_inside(path: &str, namespace: &str) -> bool {
+    match path.strip_prefix(namespace) {
+        Some(rest) => rest.is_empty() || rest.starts_with('.'),
+        None => false,
+    }
+}
+
+/// Whether the value at `path` is a key being entered to insert into a map, which is only stored by the viewer.
+fn is_draft(path: &str, drafts: &HashSet<String>) -> bool {
+    drafts.iter().any(|draft| is_inside(path, draft))
+}
+
+/// Store the values in `param` under `namespace`, and the values inside it under their own paths.
+fn recursive_insert(
+    namespace: String,
+    param: Parameter<Value>,
+    data: &mut HashMap<String, AnyData>,
+) {
+    let parameter = match param {
+        Parameter::SignedInt(value) => AnyData::SignedInt(value),
+        Parameter::UnsignedInt(value) => AnyData::UnsignedInt(value),
+        Parameter::Float(value) => AnyData::Float(value),
+        Parameter::Bool(value) => AnyData::Bool(value),
+        Parameter::String(value) => AnyData::String(value),
+        Parameter::Namespaced(namespaced) => {
+            for (name, param) in namespaced.parameters.into_iter() {
+                recursive_insert(format!("{}.{}", namespace, name), param, data);
+            }
+            return;
+        }
+    };
+    data.insert(namespace, parameter);
+}
+
+/// Store the length of each vector in `repr`, which isn't part of the vector's value.
+fn store_lengths(repr: &Namespaced<Repr>, namespace: &str, data: &mut HashMap<String, AnyData>) {
+    if repr.name == ["livemod", "vec"] {
+        data.insert(
+            format!("{}.len", namespace),
+            AnyData::UnsignedInt(*repr.parameters["len"].as_unsigned_int().unwrap()),
+        );
+    }
+    for_each_child(repr, namespace, &mut |child, child_namespace| {
+        store_lengths(child, &child_namespace, data)
+    });
+}
+
+/// Find the repr of the value at `path`, inside the value at `namespace` with the representation `repr`.
+fn find_repr<'a>(
+    repr: &'a Namespaced<Repr>,
+    namespace: &str,
+    path: &str,
+) -> Option<&'a Namespaced<Repr>> {
+    if namespace == path {
+        return Some(repr);
+    }
+    let mut found = None;
+    for_each_child(repr, namespace, &mut |child, child_namespace| {
+        if found.is_none() && is_inside(path, &child_namespace) {
+            found = find_repr(child, &child_namespace, path);
+        }
+    });
+    found
+}
+
+/// Call `f` with the repr and namespace of each value directly inside the value at `namespace`, which has the
+/// representation `repr`.
+fn for_each_child<'a>(
+    repr: &'a Namespaced<Repr>,
+    namespace: &str,
+    f: &mut dyn FnMut(&'a Namespaced<Repr>, String),
+) {
+    if repr.name[0] != "livemod" {
+        return;
+    }
+    match repr.name[1].as_str() {
+        "fields" => {
+            for (name, field) in &repr.parameters {
+                let field = field.as_namespaced().unwrap();
+                if field.name == ["livemod", "group"] {
+                    for_each_child(field, namespace, f);
+                } else {
+                    f(field, format!("{}.{}", namespace, name));
+                }
+            }
+        }
+        "struct" | "group" => for_each_child(
+            repr.parameters["fields"].as_namespaced().unwrap(),
+            namespace,
+            f,
+        ),
+        "enum" => {
+            if let Some(current) = repr.parameters.get("current") {
+                for_each_child(
+                    current.as_namespaced().unwrap(),
+                    &format!("{}.current", namespace),
+                    f,
+                );
+            }
+        }
+        "vec" => {
+            for (i, element) in &repr.parameters {
+                if i.parse::<usize>().is_ok() {
+                    f(
+                        element.as_namespaced().unwrap(),
+                        format!("{}.{}", namespace, i),
+                    );
+                }
+            }
+        }
+        "map" => {
+            let values = repr.parameters["values"].as_namespaced().unwrap();
+            for (key, value) in &values.parameters {
+                f(
+                    value.as_namespaced().unwrap(),
+                    format!("{}.values.{}", namespace, key),
+                );
+            }
+        }
+        _ => {}
     }
 }
 
+/// Construct the value of each field in the given `livemod:fields` repr.
+fn construct_fields(
+    fields: &Namespaced<Repr>,
+    namespace: String,
+    data: &HashMap<String, AnyData>,
+) -> LinkedHashMap<String, Parameter<Value>> {
+    let mut values = LinkedHashMap::new();
+    for (name, field) in &fields.parameters {
+        let field = field.as_namespaced().unwrap();
+        if field.name == ["livemod", "group"] {
+            values.extend(construct_fields(
+                field.parameters["fields"].as_namespaced().unwrap(),
+                namespace.clone(),
+                data,
+            ));
+        } else {
+            values.insert(
+                name.clone(),
+                construct_value(field, format!("{}.{}", namespace, name), data),
+            );
+        }
+    }
+    values
+}
+
+/// Evaluate a `livemod:condition` on a sibling field of the struct at `namespace`.
+fn condition_holds(condition: &Namespaced<Repr>, namespace: &str, state: &State) -> bool {
+    let field_namespace = format!(
+        "{}.{}",
+        namespace,
+        condition.parameters["field"].as_string().unwrap()
+    );
+    let holds = match condition
+        .parameters
+        .get("equals")
+        .and_then(|e| e.as_string())
+    {
+        Some(variant) => {
+            state
+                .tracked_data
+                .get(&format!("{}.variant", field_namespace))
+                == Some(&AnyData::String(variant.clone()))
+        }
+        None => state.tracked_data.get(&field_namespace) == Some(&AnyData::Bool(true)),
+    };
+    let negate = condition
+        .parameters
+        .get("negate")
+        .and_then(|n| n.as_bool())
+        .copied()
+        .unwrap_or(false);
+    holds != negate
+}
+
+/// Get the name of the first variant in the given `livemod:variants` repr.
+fn first_variant(variants: &Namespaced<Repr>) -> String {
+    variants
+        .parameters
+        .values()
+        .next()
+        .and_then(|variant| variant.as_string())
+        .cloned()
+        .unwrap_or_default()
+}
+
 #[derive(Debug, Clone)]
 enum Message {
     NewData(String, Namespaced<Repr>, Parameter<Value>),
     UpdateRepr(String, Namespaced<Repr>, Parameter<Value>),
     UpdateData(String, Parameter<Value>),
     RemoveData(String),
+    AcceptError(String, String),
     Quit,
 }
 
-fn reader_thread(sender: Sender<Message>) {
+fn reader_thread(sender: Sender<Message>, proxy: glutin::event_loop::EventLoopProxy<()>) {
     #[cfg(feature = "io_tee")]
     use io_tee::ReadExt;
 
@@ -851,9 +2248,33 @@ fn reader_thread(sender: Sender<Message>) {
                 };
                 sender.send(Message::RemoveData(name)).unwrap();
             }
+            b'e' => {
+                let name = {
+                    let mut name = Vec::new();
+                    reader.read_until(b';', &mut name).unwrap();
+                    name.pop(); // Pop delimiter
+                    String::from_utf8(name).unwrap()
+                };
+
+                let len_error = {
+                    let mut len = Vec::new();
+                    reader.read_until(b'-', &mut len).unwrap();
+                    len.pop(); // Pop delimiter
+                    String::from_utf8(len).unwrap().parse::<usize>().unwrap()
+                };
+                let error = {
+                    let mut error = vec![0u8; len_error];
+                    reader.read_exact(&mut error).unwrap();
+                    String::from_utf8(error).unwrap()
+                };
+                sender.send(Message::AcceptError(name, error)).unwrap();
+            }
             _ => {}
         }
+        // Wake the event loop to handle the message. This only fails once the event loop has closed.
+        let _ = proxy.send_event(());
     }
 
-    sender.send(Message::Quit).unwrap()
+    sender.send(Message::Quit).unwrap();
+    let _ = proxy.send_event(());
 }
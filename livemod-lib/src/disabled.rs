@@ -40,12 +40,12 @@ pub struct ModVar<T> {
 
 impl<T> ModVar<T> {
     #[inline(always)]
-    pub fn lock(&self) -> ModVarGuard<T> {
+    pub fn lock(&self) -> ModVarGuard<'_, T> {
         ModVarGuard(&self.value)
     }
 
     #[inline(always)]
-    pub fn lock_mut(&mut self) -> ModVarMutGuard<T> {
+    pub fn lock_mut(&mut self) -> ModVarMutGuard<'_, T> {
         ModVarMutGuard(&mut self.value)
     }
 }
@@ -61,7 +61,7 @@ impl<T> StaticModVar<T> {
     }
 
     #[inline(always)]
-    pub fn lock(&self) -> ModVarGuard<T> {
+    pub fn lock(&self) -> ModVarGuard<'_, T> {
         ModVarGuard(&self.value)
     }
 }
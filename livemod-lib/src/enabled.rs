@@ -2,7 +2,7 @@ use std::collections::HashMap;
 use std::io::{BufRead, BufReader, Read, Write};
 use std::marker::PhantomData;
 use std::ops::{Deref, DerefMut};
-use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::process::{ChildStdin, ChildStdout, Command, Stdio};
 use std::ptr::NonNull;
 use std::sync::mpsc::{self, Receiver, Sender};
 use std::sync::{Arc, Barrier};
@@ -153,14 +153,14 @@ pub struct ModVar<T> {
 impl<T: LiveMod> ModVar<T> {
     /// Get an immutable reference to the value in this `ModVar`. The value will not be changed
     /// by the external viewer while this reference is held.
-    pub fn lock(&self) -> ModVarGuard<T> {
+    pub fn lock(&self) -> ModVarGuard<'_, T> {
         ModVarGuard(self.value.lock())
     }
 
     /// Get a mutable reference to the value in thie `ModVar` The value will not be changed
     /// by the external viewer while this reference is held. The value in the external viewer
     /// will be updated if and only if the `ModVarMutGuard` is dereferenced mutably.
-    pub fn lock_mut(&mut self) -> ModVarMutGuard<T> {
+    pub fn lock_mut(&mut self) -> ModVarMutGuard<'_, T> {
         ModVarMutGuard(self.value.lock(), Some(UpdateMessage::new(self)))
     }
 }
@@ -188,7 +188,7 @@ impl<T> StaticModVar<T> {
 
     /// Get an immutable reference to the value in this `ModVar`. The value will not be changed
     /// by the external viewer while this reference is held.
-    pub fn lock(&self) -> ModVarGuard<T> {
+    pub fn lock(&self) -> ModVarGuard<'_, T> {
         ModVarGuard(self.value.lock())
     }
 }
@@ -200,7 +200,7 @@ impl<'a, T> Deref for ModVarGuard<'a, T> {
     type Target = T;
 
     fn deref(&self) -> &Self::Target {
-        &*self.0
+        &self.0
     }
 }
 
@@ -213,7 +213,7 @@ impl<'a, T> Deref for ModVarMutGuard<'a, T> {
     type Target = T;
 
     fn deref(&self) -> &Self::Target {
-        &*self.0
+        &self.0
     }
 }
 
@@ -222,7 +222,7 @@ impl<'a, T> DerefMut for ModVarMutGuard<'a, T> {
         if let Some(msg) = self.1.take() {
             msg.send();
         }
-        &mut *self.0
+        &mut self.0
     }
 }
 
@@ -272,19 +272,10 @@ enum Message {
     UpdatedVariable(String, ModVarHandle),
     RemoveVariable(String),
     UpdatedRepr(String),
+    AcceptError(String, String),
     Quit,
 }
 
-struct ChildDropper {
-    child: Child,
-}
-
-impl Drop for ChildDropper {
-    fn drop(&mut self) {
-        self.child.wait().unwrap();
-    }
-}
-
 fn input_thread(
     mut input: ChildStdin,
     recv: Receiver<Message>,
@@ -300,9 +291,9 @@ fn input_thread(
                     input,
                     "n{};{}-{};{}-{}",
                     &name,
-                    repr.as_bytes().len(),
+                    repr.len(),
                     repr,
-                    value.as_bytes().len(),
+                    value.len(),
                     value,
                 )
                 .unwrap();
@@ -311,7 +302,7 @@ fn input_thread(
             Message::UpdatedVariable(name, handle) => {
                 let var = unsafe { handle.var.as_ref() }.lock();
                 let value = var.get_self(ActionTarget::This).serialize();
-                writeln!(input, "s{};{}-{}", &name, value.as_bytes().len(), value,).unwrap();
+                writeln!(input, "s{};{}-{}", &name, value.len(), value,).unwrap();
             }
             Message::UpdatedRepr(name) => {
                 // Get the 'base' variable from our HashMap
@@ -326,9 +317,9 @@ fn input_thread(
                     input,
                     "u{};{}-{};{}-{}",
                     name,
-                    repr.as_bytes().len(),
+                    repr.len(),
                     repr,
-                    value.as_bytes().len(),
+                    value.len(),
                     value,
                 )
                 .unwrap();
@@ -336,6 +327,9 @@ fn input_thread(
             Message::RemoveVariable(name) => {
                 writeln!(input, "r{}", &name).unwrap();
             }
+            Message::AcceptError(path, error) => {
+                writeln!(input, "e{};{}-{}", path, error.len(), error).unwrap();
+            }
             Message::Quit => {
                 break;
             }
@@ -405,12 +399,35 @@ fn output_thread(
                 };
 
                 // Set the variable
-                if referenced_var
+                match referenced_var
                     .accept(ActionTarget::from_name_and_fields(&namespaced_name), value)
                 {
-                    sender
-                        .send(Message::UpdatedRepr(namespaced_name[0].to_owned()))
-                        .unwrap();
+                    Ok(true) => {
+                        sender
+                            .send(Message::UpdatedRepr(namespaced_name[0].to_owned()))
+                            .unwrap();
+                    }
+                    Ok(false) => {}
+                    Err(error) => {
+                        // The viewer is probably out of date, so send it the current state of the variable too
+                        sender
+                            .send(Message::AcceptError(name.clone(), error.to_string()))
+                            .unwrap();
+                        sender
+                            .send(Message::UpdatedRepr(namespaced_name[0].to_owned()))
+                            .unwrap();
+                    }
+                }
+            }
+            b'g' => {
+                // The viewer wants the current state of a variable
+                let name = {
+                    let mut name = String::new();
+                    reader.read_line(&mut name).unwrap();
+                    name.trim_end().to_owned()
+                };
+                if variables.read().contains_key(&name) {
+                    sender.send(Message::UpdatedRepr(name)).unwrap();
                 }
             }
             _ => {}
@@ -0,0 +1,212 @@
+//! `LiveMod` implementations for [`glam`] types, enabled by the `glam` feature.
+
+use glam::{
+    DMat2, DMat3, DMat4, DQuat, DVec2, DVec3, DVec4, EulerRot, IVec2, IVec3, IVec4, Mat2, Mat3,
+    Mat4, Quat, UVec2, UVec3, UVec4, Vec2, Vec3, Vec3A, Vec4,
+};
+
+use crate::{
+    AcceptError, ActionTarget, BuiltinRepr, LiveMod, LiveModCtor, Namespaced, Parameter, Repr,
+    Value,
+};
+
+macro_rules! impl_vector {
+    ($(($ty:ty, $t:ty, [$($component:ident),*])),*) => {
+        $(
+        impl LiveMod for $ty {
+            fn repr_default(&self, target: ActionTarget) -> Namespaced<Repr> {
+                if target.strip_one_field().is_some() {
+                    <$t>::repr_static()
+                } else {
+                    BuiltinRepr::Vector {
+                        components: vec![
+                            $((stringify!($component).to_owned(), <$t>::repr_static())),*
+                        ],
+                    }
+                    .into()
+                }
+            }
+
+            fn accept(&mut self, target: ActionTarget, value: Parameter<Value>) -> Result<bool, AcceptError> {
+                if let Some((field, field_target)) = target.strip_one_field() {
+                    match field {
+                        $(stringify!($component) => self.$component.accept(field_target, value),)*
+                        _ => Err(AcceptError::UnknownField(field.to_owned())),
+                    }
+                } else {
+                    Err(AcceptError::MissingField)
+                }
+            }
+
+            fn get_self(&self, target: ActionTarget) -> Parameter<Value> {
+                if let Some((field, field_target)) = target.strip_one_field() {
+                    match field {
+                        $(stringify!($component) => self.$component.get_self(field_target),)*
+                        _ => Namespaced::unknown_field_value(),
+                    }
+                } else {
+                    Parameter::Namespaced(Namespaced::new(
+                        vec!["livemod".to_owned(), "vector".to_owned()],
+                        IntoIterator::into_iter([
+                            $((
+                                stringify!($component).to_owned(),
+                                self.$component.get_self(ActionTarget::This),
+                            )),*
+                        ])
+                        .collect(),
+                    ))
+                }
+            }
+        }
+        )*
+    };
+}
+
+impl_vector!(
+    (Vec2, f32, [x, y]),
+    (Vec3, f32, [x, y, z]),
+    (Vec3A, f32, [x, y, z]),
+    (Vec4, f32, [x, y, z, w]),
+    (DVec2, f64, [x, y]),
+    (DVec3, f64, [x, y, z]),
+    (DVec4, f64, [x, y, z, w]),
+    (IVec2, i32, [x, y]),
+    (IVec3, i32, [x, y, z]),
+    (IVec4, i32, [x, y, z, w]),
+    (UVec2, u32, [x, y]),
+    (UVec3, u32, [x, y, z]),
+    (UVec4, u32, [x, y, z, w])
+);
+
+/// Quaternions are represented by their euler angles, in the order yaw, pitch, roll.
+macro_rules! impl_quat {
+    ($(($ty:ty, $t:ty)),*) => {
+        $(
+        impl LiveMod for $ty {
+            fn repr_default(&self, target: ActionTarget) -> Namespaced<Repr> {
+                if target.strip_one_field().is_some() {
+                    BuiltinRepr::Angle { wrap: None }.into()
+                } else {
+                    BuiltinRepr::Vector {
+                        components: ["yaw", "pitch", "roll"]
+                            .iter()
+                            .map(|component| {
+                                (
+                                    component.to_string(),
+                                    BuiltinRepr::Angle { wrap: None }.into(),
+                                )
+                            })
+                            .collect(),
+                    }
+                    .into()
+                }
+            }
+
+            fn accept(&mut self, target: ActionTarget, value: Parameter<Value>) -> Result<bool, AcceptError> {
+                if let Some((field, field_target)) = target.strip_one_field() {
+                    let (mut yaw, mut pitch, mut roll) = self.to_euler(EulerRot::YXZ);
+                    match field {
+                        "yaw" => yaw.accept(field_target, value)?,
+                        "pitch" => pitch.accept(field_target, value)?,
+                        "roll" => roll.accept(field_target, value)?,
+                        _ => return Err(AcceptError::UnknownField(field.to_owned())),
+                    };
+                    *self = <$ty>::from_euler(EulerRot::YXZ, yaw, pitch, roll);
+                    // The rotation may be decomposed into different angles, which the viewer needs to be sent
+                    Ok(true)
+                } else {
+                    Err(AcceptError::MissingField)
+                }
+            }
+
+            fn get_self(&self, target: ActionTarget) -> Parameter<Value> {
+                let (yaw, pitch, roll) = self.to_euler(EulerRot::YXZ);
+                if let Some((field, field_target)) = target.strip_one_field() {
+                    match field {
+                        "yaw" => yaw.get_self(field_target),
+                        "pitch" => pitch.get_self(field_target),
+                        "roll" => roll.get_self(field_target),
+                        _ => Namespaced::unknown_field_value(),
+                    }
+                } else {
+                    Parameter::Namespaced(Namespaced::new(
+                        vec!["livemod".to_owned(), "vector".to_owned()],
+                        IntoIterator::into_iter([
+                            ("yaw".to_owned(), yaw.get_self(ActionTarget::This)),
+                            ("pitch".to_owned(), pitch.get_self(ActionTarget::This)),
+                            ("roll".to_owned(), roll.get_self(ActionTarget::This)),
+                        ])
+                        .collect(),
+                    ))
+                }
+            }
+        }
+        )*
+    };
+}
+
+impl_quat!((Quat, f32), (DQuat, f64));
+
+/// Matrices are represented as a structure of their column vectors.
+macro_rules! impl_matrix {
+    ($(($ty:ident, [$($axis:ident),*])),*) => {
+        $(
+        impl LiveMod for $ty {
+            fn repr_default(&self, target: ActionTarget) -> Namespaced<Repr> {
+                if let Some((field, field_target)) = target.strip_one_field() {
+                    match field {
+                        $(stringify!($axis) => self.$axis.repr_default(field_target),)*
+                        _ => Namespaced::unknown_field_repr(),
+                    }
+                } else {
+                    Namespaced::basic_structure_repr(
+                        stringify!($ty),
+                        &[
+                            $((
+                                stringify!($axis).to_owned(),
+                                self.$axis.repr_default(ActionTarget::This),
+                            )),*
+                        ],
+                    )
+                }
+            }
+
+            fn accept(&mut self, target: ActionTarget, value: Parameter<Value>) -> Result<bool, AcceptError> {
+                if let Some((field, field_target)) = target.strip_one_field() {
+                    match field {
+                        $(stringify!($axis) => self.$axis.accept(field_target, value),)*
+                        _ => Err(AcceptError::UnknownField(field.to_owned())),
+                    }
+                } else {
+                    Err(AcceptError::MissingField)
+                }
+            }
+
+            fn get_self(&self, target: ActionTarget) -> Parameter<Value> {
+                if let Some((field, field_target)) = target.strip_one_field() {
+                    match field {
+                        $(stringify!($axis) => self.$axis.get_self(field_target),)*
+                        _ => Namespaced::unknown_field_value(),
+                    }
+                } else {
+                    Parameter::Namespaced(Namespaced::basic_structure_value(&[
+                        $((
+                            stringify!($axis).to_owned(),
+                            self.$axis.get_self(ActionTarget::This),
+                        )),*
+                    ]))
+                }
+            }
+        }
+        )*
+    };
+}
+
+impl_matrix!(
+    (Mat2, [x_axis, y_axis]),
+    (Mat3, [x_axis, y_axis, z_axis]),
+    (Mat4, [x_axis, y_axis, z_axis, w_axis]),
+    (DMat2, [x_axis, y_axis]),
+    (DMat3, [x_axis, y_axis, z_axis]),
+    (DMat4, [x_axis, y_axis, z_axis, w_axis])
+);
@@ -1,24 +1,29 @@
 //! # livemod - Runtime modification of program parameters
 
-use std::array::IntoIter;
+use std::convert::TryFrom;
 use std::error::Error;
 use std::fmt::Display;
 use std::hash::Hash;
 use std::iter::FromIterator;
-use std::ops::RangeInclusive;
+use std::ops::{Range, RangeInclusive};
 use std::string::FromUtf8Error;
 
 pub use hashlink;
 use hashlink::LinkedHashMap;
 
 #[cfg(feature = "livemod-derive")]
-pub use livemod_derive::LiveMod;
+pub use livemod_derive::{LiveMod, LiveModCtor};
 
 #[cfg_attr(not(feature = "disabled"), allow(dead_code))]
 mod disabled;
 #[cfg_attr(feature = "disabled", allow(dead_code))]
 mod enabled;
 
+#[cfg(feature = "glam")]
+mod glam_impl;
+#[cfg(feature = "nalgebra")]
+mod nalgebra_impl;
+
 #[cfg(not(feature = "disabled"))]
 pub use enabled::*;
 
@@ -75,6 +80,52 @@ pub enum BuiltinRepr {
     ///
     /// Maps to `livemod:string`
     String { multiline: bool },
+    /// A range of values, with `bounds` being the representation of each end of the range.
+    ///
+    /// Maps to `livemod:range`
+    Range {
+        inclusive: bool,
+        bounds: Namespaced<Repr>,
+    },
+    /// An RGB or RGBA colour, stored as described by `storage`.
+    ///
+    /// Maps to `livemod:color`
+    Color {
+        alpha: bool,
+        linear: bool,
+        storage: ColorStorage,
+    },
+    /// An angle in radians, optionally wrapping around to stay within `wrap`.
+    ///
+    /// Maps to `livemod:angle`
+    Angle { wrap: Option<Range<f64>> },
+    /// A fixed-size vector, with each named component displayed in a single row.
+    ///
+    /// Maps to `livemod:vector`
+    Vector {
+        components: Vec<(String, Namespaced<Repr>)>,
+    },
+}
+
+/// The way in which the components of a [`BuiltinRepr::Color`] are stored.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorStorage {
+    /// One floating-point field per component, named `0` to `3`, each in the range `0.0..=1.0`.
+    Float,
+    /// One unsigned integer field per component, named `0` to `3`, each in the range `0..=255`.
+    Byte,
+    /// A single unsigned integer, packed as `0xRRGGBB` or `0xRRGGBBAA`.
+    Packed,
+}
+
+impl ColorStorage {
+    fn name(self) -> &'static str {
+        match self {
+            ColorStorage::Float => "float",
+            ColorStorage::Byte => "byte",
+            ColorStorage::Packed => "packed",
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -82,6 +133,7 @@ pub enum DeserializeError {
     UnexpectedEOF,
     UnexpectedTerminator { previous: String },
     InvalidParameter(u8),
+    InvalidNumber,
     NonUTF8(FromUtf8Error),
 }
 
@@ -97,6 +149,7 @@ impl Display for DeserializeError {
             DeserializeError::UnexpectedEOF => write!(f, "Unexpected end-of-file"),
             DeserializeError::UnexpectedTerminator { previous } => write!(f, "Unexpected terminator in middle of {}", previous),
             DeserializeError::InvalidParameter(b) => write!(f, "Invalid parameter type: {}", *b as char),
+            DeserializeError::InvalidNumber => write!(f, "Invalid number"),
             DeserializeError::NonUTF8(_) => write!(f, "Expected UTF-8"),
         }
     }
@@ -111,6 +164,39 @@ impl Error for DeserializeError {
     }
 }
 
+/// An error from applying a value received from the viewer, usually because the viewer's copy of the variable is out
+/// of date.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AcceptError {
+    /// The target has no field with the given name.
+    UnknownField(String),
+    /// The target has no variant with the given name.
+    UnknownVariant(String),
+    /// The value was sent to a struct, enum or collection itself, rather than one of its fields.
+    MissingField,
+    /// The value isn't of the type the target expects.
+    WrongType { expected: &'static str },
+    /// The value can't be stored in the target.
+    OutOfRange,
+    /// The map already has an entry with the given key.
+    DuplicateKey,
+}
+
+impl Display for AcceptError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AcceptError::UnknownField(field) => write!(f, "Unknown field `{}`", field),
+            AcceptError::UnknownVariant(variant) => write!(f, "Unknown variant `{}`", variant),
+            AcceptError::MissingField => write!(f, "Expected a field name"),
+            AcceptError::WrongType { expected } => write!(f, "Expected {}", expected),
+            AcceptError::OutOfRange => write!(f, "Value out of range"),
+            AcceptError::DuplicateKey => write!(f, "An entry with this key already exists"),
+        }
+    }
+}
+
+impl Error for AcceptError {}
+
 /// Marker type to specify a representation parameter
 #[derive(Clone, Copy, Debug)]
 pub struct Repr;
@@ -138,17 +224,17 @@ impl<T> Parameter<T> {
             Parameter::Float(f) => format!("d{}", f),
             Parameter::Bool(true) => "t".to_owned(),
             Parameter::Bool(false) => "f".to_owned(),
-            Parameter::String(s) => format!("s{}-{}", s.as_bytes().len(), s),
+            Parameter::String(s) => format!("s{}-{}", s.len(), s),
             Parameter::Namespaced(n) => format!("n{}", n.serialize()),
         }
     }
 
     pub fn deserialize(mut s: &mut dyn Iterator<Item = u8>) -> Result<Parameter<T>, DeserializeError> {
-        Ok(match s.next().unwrap() {
+        Ok(match s.next().ok_or(DeserializeError::UnexpectedEOF)? {
             // Terminating ';' consumed by take_while
-            b'i' => Parameter::SignedInt(s.take_while(|b| b.is_ascii_digit() || *b == b'-').map(|b| b as char).collect::<String>().parse().unwrap()),
-            b'd' => Parameter::Float(s.take_while(|b| b.is_ascii_digit() || *b == b'-' || *b == b'.').map(|b| b as char).collect::<String>().parse().unwrap()),
-            b'u' => Parameter::UnsignedInt(s.take_while(|b| b.is_ascii_digit() || *b == b'-').map(|b| b as char).collect::<String>().parse().unwrap()),
+            b'i' => Parameter::SignedInt(s.take_while(|b| b.is_ascii_digit() || *b == b'-').map(|b| b as char).collect::<String>().parse().map_err(|_| DeserializeError::InvalidNumber)?),
+            b'd' => Parameter::Float(s.take_while(|b| b.is_ascii_digit() || *b == b'-' || *b == b'.').map(|b| b as char).collect::<String>().parse().map_err(|_| DeserializeError::InvalidNumber)?),
+            b'u' => Parameter::UnsignedInt(s.take_while(|b| b.is_ascii_digit() || *b == b'-').map(|b| b as char).collect::<String>().parse().map_err(|_| DeserializeError::InvalidNumber)?),
             b't' => {
                 s.next(); // consume the terminating `;`
                 Parameter::Bool(true)
@@ -158,7 +244,7 @@ impl<T> Parameter<T> {
                 Parameter::Bool(false)
             },
             b's' => {
-                let len = (&mut s).take_while(|b| b.is_ascii_digit()).map(|b| b as char).collect::<String>().parse().unwrap();
+                let len = (&mut s).take_while(|b| b.is_ascii_digit()).map(|b| b as char).collect::<String>().parse().map_err(|_| DeserializeError::InvalidNumber)?;
                 // take_while will have consumed the separator `-`
                 let string = String::from_utf8(s.take(len).collect())?;
                 s.next(); // consume the terminating `;`
@@ -324,11 +410,7 @@ impl<T> Namespaced<T> {
             let key = {
                 let mut key = match s.next() {
                     Some(b'}') => break,
-                    Some(b) => {
-                        let mut key = Vec::new();
-                        key.push(b);
-                        key
-                    },
+                    Some(b) => vec![b],
                     None => return Err(DeserializeError::UnexpectedEOF),
                 };
                 loop {
@@ -360,9 +442,12 @@ impl Namespaced<Repr> {
         name: &str,
         fields: &[(String, Namespaced<Repr>)],
     ) -> Namespaced<Repr> {
+        if cfg!(debug_assertions) {
+            assert_unique_field_names(fields.iter().map(|(name, field)| (name, Some(field))));
+        }
         Namespaced {
             name: vec!["livemod".to_owned(), "struct".to_owned()],
-            parameters: LinkedHashMap::from_iter(IntoIter::new([
+            parameters: LinkedHashMap::from_iter(IntoIterator::into_iter([
                 ("name".to_owned(), Parameter::String(name.to_owned())),
                 (
                     "fields".to_owned(),
@@ -380,6 +465,13 @@ impl Namespaced<Repr> {
     }
 
     pub fn fields_repr(fields: &[(String, Parameter<Repr>)]) -> Namespaced<Repr> {
+        if cfg!(debug_assertions) {
+            assert_unique_field_names(
+                fields
+                    .iter()
+                    .map(|(name, field)| (name, field.as_namespaced())),
+            );
+        }
         Namespaced {
             name: vec!["livemod".to_owned(), "fields".to_owned()],
             parameters: LinkedHashMap::from_iter(
@@ -390,6 +482,89 @@ impl Namespaced<Repr> {
             _marker: std::marker::PhantomData,
         }
     }
+
+    /// Create a group of fields, which is displayed as its own section without changing the path to each field.
+    ///
+    /// Maps to `livemod:group`
+    pub fn group_repr(name: &str, fields: &[(String, Namespaced<Repr>)]) -> Namespaced<Repr> {
+        let mut repr = Namespaced::basic_structure_repr(name, fields);
+        repr.name[1] = "group".to_owned();
+        repr
+    }
+
+    /// Create a condition on the value of a sibling field, which holds if the field is a `true` bool, or if `equals`
+    /// is given, if the field is an enum with that variant selected. The result is inverted if `negate` is `true`.
+    ///
+    /// Maps to `livemod:condition`
+    pub fn condition_repr(field: &str, equals: Option<&str>, negate: bool) -> Namespaced<Repr> {
+        let mut parameters = LinkedHashMap::new();
+        parameters.insert("field".to_owned(), Parameter::String(field.to_owned()));
+        if let Some(equals) = equals {
+            parameters.insert("equals".to_owned(), Parameter::String(equals.to_owned()));
+        }
+        parameters.insert("negate".to_owned(), Parameter::Bool(negate));
+        Namespaced::new(
+            vec!["livemod".to_owned(), "condition".to_owned()],
+            parameters,
+        )
+    }
+
+    /// The representation of a field which doesn't exist, which is an unnamed structure with no fields. This is returned
+    /// by [`LiveMod::repr_default`] when its target names an unknown field.
+    pub fn unknown_field_repr() -> Namespaced<Repr> {
+        Namespaced::basic_structure_repr("", &[])
+    }
+
+    /// The names of the fields in this structure representation, including those inside any groups.
+    pub fn structure_field_names(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        if let Some(fields) = self
+            .parameters
+            .get("fields")
+            .and_then(|fields| fields.as_namespaced())
+        {
+            for (name, field) in &fields.parameters {
+                match field.as_namespaced() {
+                    Some(group) if group.name == ["livemod", "group"] => {
+                        names.extend(group.structure_field_names())
+                    }
+                    _ => names.push(name.clone()),
+                }
+            }
+        }
+        names
+    }
+
+    /// Take the fields out of a structure representation, such as one created by [`Namespaced::basic_structure_repr`].
+    pub fn into_structure_fields(mut self) -> Vec<(String, Namespaced<Repr>)> {
+        match self.parameters.remove("fields") {
+            Some(Parameter::Namespaced(fields)) => fields
+                .parameters
+                .into_iter()
+                .filter_map(|(name, field)| Some((name, field.try_into_namespaced().ok()?)))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Check that no two fields of a structure have the same name, including the fields inside its groups, as they would
+/// otherwise replace each other. This can happen when a flattened struct has a field with the same name as a field or
+/// group of the struct containing it.
+fn assert_unique_field_names<'a>(
+    fields: impl Iterator<Item = (&'a String, Option<&'a Namespaced<Repr>>)>,
+) {
+    let mut names = Vec::new();
+    for (name, field) in fields {
+        names.push(name.clone());
+        if let Some(group) = field.filter(|field| field.name == ["livemod", "group"]) {
+            names.extend(group.structure_field_names());
+        }
+    }
+    names.sort();
+    if let Some(names) = names.windows(2).find(|names| names[0] == names[1]) {
+        panic!("More than one field is named `{}`", names[0]);
+    }
 }
 
 impl Namespaced<Value> {
@@ -405,6 +580,37 @@ impl Namespaced<Value> {
         }
     }
 
+    /// Take the fields out of a structure value, such as one created by [`Namespaced::basic_structure_value`].
+    pub fn structure_values(value: Parameter<Value>) -> Vec<(String, Parameter<Value>)> {
+        match value {
+            Parameter::Namespaced(value) => value.parameters.into_iter().collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Remove the fields described by the structure representation `repr`, and return them as a structure value of
+    /// their own.
+    pub fn take_structure_values(&mut self, repr: &Namespaced<Repr>) -> Parameter<Value> {
+        Parameter::Namespaced(Namespaced {
+            name: vec!["livemod".to_owned(), "struct".to_owned()],
+            parameters: repr
+                .structure_field_names()
+                .into_iter()
+                .filter_map(|name| {
+                    let value = self.parameters.remove(&name)?;
+                    Some((name, value))
+                })
+                .collect(),
+            _marker: std::marker::PhantomData,
+        })
+    }
+
+    /// The value of a field which doesn't exist, matching [`Namespaced::unknown_field_repr`]. This is returned by
+    /// [`LiveMod::get_self`] when its target names an unknown field.
+    pub fn unknown_field_value() -> Parameter<Value> {
+        Parameter::Namespaced(Namespaced::basic_structure_value(&[]))
+    }
+
     pub fn fields_value(fields: &[(String, Parameter<Value>)]) -> Namespaced<Value> {
         Namespaced {
             name: vec!["livemod".to_owned(), "fields".to_owned()],
@@ -428,7 +634,7 @@ impl From<BuiltinRepr> for Namespaced<Repr> {
             },
             BuiltinRepr::String { multiline } => Namespaced {
                 name: vec!["livemod".to_owned(), "string".to_owned()],
-                parameters: LinkedHashMap::from_iter(IntoIter::new([(
+                parameters: LinkedHashMap::from_iter(IntoIterator::into_iter([(
                     "multiline".to_owned(),
                     Parameter::Bool(multiline),
                 )])),
@@ -436,7 +642,7 @@ impl From<BuiltinRepr> for Namespaced<Repr> {
             },
             BuiltinRepr::SignedInteger { min, max } => Namespaced {
                 name: vec!["livemod".to_owned(), "sint".to_owned()],
-                parameters: LinkedHashMap::from_iter(IntoIter::new([
+                parameters: LinkedHashMap::from_iter(IntoIterator::into_iter([
                     ("min".to_owned(), Parameter::SignedInt(min)),
                     ("max".to_owned(), Parameter::SignedInt(max)),
                 ])),
@@ -444,7 +650,7 @@ impl From<BuiltinRepr> for Namespaced<Repr> {
             },
             BuiltinRepr::UnsignedInteger { min, max } => Namespaced {
                 name: vec!["livemod".to_owned(), "uint".to_owned()],
-                parameters: LinkedHashMap::from_iter(IntoIter::new([
+                parameters: LinkedHashMap::from_iter(IntoIterator::into_iter([
                     ("min".to_owned(), Parameter::UnsignedInt(min)),
                     ("max".to_owned(), Parameter::UnsignedInt(max)),
                 ])),
@@ -452,7 +658,7 @@ impl From<BuiltinRepr> for Namespaced<Repr> {
             },
             BuiltinRepr::Float { min, max } => Namespaced {
                 name: vec!["livemod".to_owned(), "float".to_owned()],
-                parameters: LinkedHashMap::from_iter(IntoIter::new([
+                parameters: LinkedHashMap::from_iter(IntoIterator::into_iter([
                     ("min".to_owned(), Parameter::Float(min)),
                     ("max".to_owned(), Parameter::Float(max)),
                 ])),
@@ -465,7 +671,7 @@ impl From<BuiltinRepr> for Namespaced<Repr> {
                 suggested_max,
             } => Namespaced {
                 name: vec!["livemod".to_owned(), "sint".to_owned()],
-                parameters: LinkedHashMap::from_iter(IntoIter::new([
+                parameters: LinkedHashMap::from_iter(IntoIterator::into_iter([
                     ("min".to_owned(), Parameter::SignedInt(storage_min)),
                     ("max".to_owned(), Parameter::SignedInt(storage_max)),
                     (
@@ -486,7 +692,7 @@ impl From<BuiltinRepr> for Namespaced<Repr> {
                 suggested_max,
             } => Namespaced {
                 name: vec!["livemod".to_owned(), "uint".to_owned()],
-                parameters: LinkedHashMap::from_iter(IntoIter::new([
+                parameters: LinkedHashMap::from_iter(IntoIterator::into_iter([
                     ("min".to_owned(), Parameter::UnsignedInt(storage_min)),
                     ("max".to_owned(), Parameter::UnsignedInt(storage_max)),
                     (
@@ -507,7 +713,7 @@ impl From<BuiltinRepr> for Namespaced<Repr> {
                 suggested_max,
             } => Namespaced {
                 name: vec!["livemod".to_owned(), "float".to_owned()],
-                parameters: LinkedHashMap::from_iter(IntoIter::new([
+                parameters: LinkedHashMap::from_iter(IntoIterator::into_iter([
                     ("min".to_owned(), Parameter::Float(storage_min)),
                     ("max".to_owned(), Parameter::Float(storage_max)),
                     ("suggested_min".to_owned(), Parameter::Float(suggested_min)),
@@ -515,11 +721,57 @@ impl From<BuiltinRepr> for Namespaced<Repr> {
                 ])),
                 _marker: std::marker::PhantomData,
             },
+            BuiltinRepr::Range { inclusive, bounds } => Namespaced {
+                name: vec!["livemod".to_owned(), "range".to_owned()],
+                parameters: LinkedHashMap::from_iter(IntoIterator::into_iter([
+                    ("inclusive".to_owned(), Parameter::Bool(inclusive)),
+                    ("bounds".to_owned(), Parameter::Namespaced(bounds)),
+                ])),
+                _marker: std::marker::PhantomData,
+            },
+            BuiltinRepr::Color {
+                alpha,
+                linear,
+                storage,
+            } => Namespaced {
+                name: vec!["livemod".to_owned(), "color".to_owned()],
+                parameters: LinkedHashMap::from_iter(IntoIterator::into_iter([
+                    ("alpha".to_owned(), Parameter::Bool(alpha)),
+                    ("linear".to_owned(), Parameter::Bool(linear)),
+                    (
+                        "storage".to_owned(),
+                        Parameter::String(storage.name().to_owned()),
+                    ),
+                ])),
+                _marker: std::marker::PhantomData,
+            },
+            BuiltinRepr::Angle { wrap } => Namespaced {
+                name: vec!["livemod".to_owned(), "angle".to_owned()],
+                parameters: wrap
+                    .into_iter()
+                    .flat_map(|wrap| {
+                        IntoIterator::into_iter([
+                            ("wrap_min".to_owned(), Parameter::Float(wrap.start)),
+                            ("wrap_max".to_owned(), Parameter::Float(wrap.end)),
+                        ])
+                    })
+                    .collect(),
+                _marker: std::marker::PhantomData,
+            },
+            BuiltinRepr::Vector { components } => Namespaced {
+                name: vec!["livemod".to_owned(), "vector".to_owned()],
+                parameters: components
+                    .into_iter()
+                    .map(|(name, repr)| (name, Parameter::Namespaced(repr)))
+                    .collect(),
+                _marker: std::marker::PhantomData,
+            },
         }
     }
 }
 
 /// The target of a method call on a [`LiveMod`] variable.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ActionTarget<'a, 'b> {
     /// The variable itself.
     This,
@@ -536,8 +788,9 @@ impl ActionTarget<'_, '_> {
     /// Create an `ActionTarget` by stripping the first element off the given slice.
     ///
     /// ```
-    /// assert_eq!(ActionTarget::from_name_and_fields(["foo"]), ActionTarget::This);
-    /// assert_eq!(ActionTarget::from_name_and_fields(["foo", "bar"]), ActionTarget::Field(&["bar"]));
+    /// # use livemod::ActionTarget;
+    /// assert_eq!(ActionTarget::from_name_and_fields(&["foo"]), ActionTarget::This);
+    /// assert_eq!(ActionTarget::from_name_and_fields(&["foo", "bar"]), ActionTarget::Field(&["bar"]));
     /// ```
     pub fn from_name_and_fields<'a, 'b>(slice: &'a [&'b str]) -> ActionTarget<'a, 'b> {
         if slice.len() < 2 {
@@ -551,6 +804,7 @@ impl ActionTarget<'_, '_> {
     /// Otherwise, return `None`.
     ///
     /// ```
+    /// # use livemod::ActionTarget;
     /// assert_eq!(ActionTarget::This.strip_one_field(), None);
     /// assert_eq!(ActionTarget::Field(&["foo"]).strip_one_field(), Some(("foo", ActionTarget::This)));
     /// assert_eq!(ActionTarget::Field(&["foo", "bar"]).strip_one_field(), Some(("foo", ActionTarget::Field(&["bar"]))));
@@ -561,20 +815,43 @@ impl ActionTarget<'_, '_> {
             Self::Field(fields) => Some((fields[0], ActionTarget::from_name_and_fields(fields))),
         }
     }
+
+    /// Return an [`AcceptError::UnknownField`] if this is a `ActionTarget::Field`, for values which don't have any
+    /// fields.
+    ///
+    /// ```
+    /// # use livemod::{AcceptError, ActionTarget};
+    /// assert_eq!(ActionTarget::This.expect_this(), Ok(()));
+    /// assert_eq!(ActionTarget::Field(&["foo"]).expect_this(), Err(AcceptError::UnknownField("foo".to_owned())));
+    /// ```
+    pub fn expect_this(&self) -> Result<(), AcceptError> {
+        match self.strip_one_field() {
+            Some((field, _)) => Err(AcceptError::UnknownField(field.to_owned())),
+            None => Ok(()),
+        }
+    }
 }
 
 /// Data which can be modified by the LiveMod API
 pub trait LiveMod: Send {
     /// The default representation of the data.
     ///
-    /// The representation of the data may be dependent on the current value of `self`.
+    /// The representation of the data may be dependent on the current value of `self`. If `target` names a field which
+    /// doesn't exist, this returns [`Namespaced::unknown_field_repr`].
     fn repr_default(&self, target: ActionTarget) -> Namespaced<Repr>;
 
     /// Get the current value of `self` in the LiveMod message format.
+    ///
+    /// If `target` names a field which doesn't exist, this returns [`Namespaced::unknown_field_value`].
     fn get_self(&self, target: ActionTarget) -> Parameter<Value>;
 
-    /// Update this data with the given value
-    fn accept(&mut self, target: ActionTarget, value: Parameter<Value>) -> bool;
+    /// Update this data with the given value, returning whether the value should be sent back to the viewer, because
+    /// it was changed in a way the viewer doesn't know about, or an error if the target or value isn't valid.
+    fn accept(
+        &mut self,
+        target: ActionTarget,
+        value: Parameter<Value>,
+    ) -> Result<bool, AcceptError>;
 }
 
 /// Data which provides extra guarantees about its representation.
@@ -588,9 +865,21 @@ pub trait LiveModCtor: LiveMod {
         Self: Sized;
 }
 
+/// A struct whose fields can be displayed as part of another struct with `#[livemod(flatten)]`. This is implemented by
+/// `#[derive(LiveMod)]` for structs.
+pub trait LiveModStruct: LiveMod {
+    /// Whether the struct has a field with the given name, including the fields of any flattened fields.
+    fn has_field(name: &str) -> bool;
+}
+
 /// Provider of an alternate representation for a LiveMod type
 pub trait LiveModRepr<T> {
     fn repr(&self, cur: &T) -> Namespaced<Repr>;
+
+    /// Correct a value accepted from the viewer to fit this representation, returning `true` if it was changed.
+    fn correct(&self, _value: &mut T) -> bool {
+        false
+    }
 }
 
 pub struct DefaultRepr;
@@ -620,6 +909,24 @@ macro_rules! impl_slider {
                     }.into()
                 }
             }
+
+            impl LiveModRepr<Range<$t>> for Slider<$t> {
+                fn repr(&self, cur: &Range<$t>) -> Namespaced<Repr> {
+                    BuiltinRepr::Range {
+                        inclusive: false,
+                        bounds: self.repr(&cur.start),
+                    }.into()
+                }
+            }
+
+            impl LiveModRepr<RangeInclusive<$t>> for Slider<$t> {
+                fn repr(&self, cur: &RangeInclusive<$t>) -> Namespaced<Repr> {
+                    BuiltinRepr::Range {
+                        inclusive: true,
+                        bounds: self.repr(cur.start()),
+                    }.into()
+                }
+            }
         )*
     };
 }
@@ -637,6 +944,190 @@ impl_slider!(
     (f64, FloatSlider)
 );
 
+impl<T> Slider<T> {
+    /// Display the slider on a logarithmic scale.
+    pub fn logarithmic(self) -> Styled<Self> {
+        Styled::new(self).logarithmic()
+    }
+
+    /// Snap values to multiples of `step`.
+    pub fn step(self, step: impl Into<f64>) -> Styled<Self> {
+        Styled::new(self).step(step)
+    }
+
+    /// Display values with `precision` decimal places.
+    pub fn precision(self, precision: u64) -> Styled<Self> {
+        Styled::new(self).precision(precision)
+    }
+
+    /// Display `unit` after the value.
+    pub fn unit(self, unit: &str) -> Styled<Self> {
+        Styled::new(self).unit(unit)
+    }
+}
+
+/// Representation for numeric values which may only be stored within the given bounds.
+///
+/// If both bounds are given, the value is displayed as a slider between them.
+pub struct Bounds<T> {
+    pub min: Option<T>,
+    pub max: Option<T>,
+}
+
+macro_rules! impl_bounds {
+    ($(($t:ty, $slider:ident, $var:ident)),*) => {
+        $(
+            impl LiveModRepr<$t> for Bounds<$t> {
+                fn repr(&self, _cur: &$t) -> Namespaced<Repr> {
+                    let min = self.min.unwrap_or(<$t>::MIN).into();
+                    let max = self.max.unwrap_or(<$t>::MAX).into();
+                    if self.min.is_some() && self.max.is_some() {
+                        BuiltinRepr::$slider {
+                            suggested_min: min,
+                            suggested_max: max,
+                            storage_min: min,
+                            storage_max: max,
+                        }.into()
+                    } else {
+                        BuiltinRepr::$var { min, max }.into()
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_bounds!(
+    (i8, SignedSlider, SignedInteger),
+    (i16, SignedSlider, SignedInteger),
+    (i32, SignedSlider, SignedInteger),
+    (i64, SignedSlider, SignedInteger),
+    (u8, UnsignedSlider, UnsignedInteger),
+    (u16, UnsignedSlider, UnsignedInteger),
+    (u32, UnsignedSlider, UnsignedInteger),
+    (u64, UnsignedSlider, UnsignedInteger),
+    (f32, FloatSlider, Float),
+    (f64, FloatSlider, Float)
+);
+
+/// Display options for the numeric builtins `livemod:sint`, `livemod:uint` and `livemod:float`.
+///
+/// These are carried as the optional parameters `logarithmic`, `step`, `precision` and `unit`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NumberStyle {
+    pub logarithmic: bool,
+    pub step: Option<f64>,
+    pub precision: Option<u64>,
+    pub unit: Option<String>,
+}
+
+impl NumberStyle {
+    /// Read the display options from the parameters of a numeric repr.
+    pub fn from_repr(repr: &Namespaced<Repr>) -> NumberStyle {
+        NumberStyle {
+            logarithmic: repr
+                .parameters
+                .get("logarithmic")
+                .and_then(|p| p.as_bool().copied())
+                .unwrap_or(false),
+            step: repr
+                .parameters
+                .get("step")
+                .and_then(|p| p.as_float().copied()),
+            precision: repr
+                .parameters
+                .get("precision")
+                .and_then(|p| p.as_unsigned_int().copied()),
+            unit: repr
+                .parameters
+                .get("unit")
+                .and_then(|p| p.as_string().cloned()),
+        }
+    }
+
+    /// Add the display options to the parameters of a numeric repr.
+    ///
+    /// If `repr` is a `livemod:range`, the options are applied to its bounds instead.
+    pub fn apply(&self, repr: &mut Namespaced<Repr>) {
+        if repr.name[..] == ["livemod", "range"] {
+            if let Some(Parameter::Namespaced(bounds)) = repr.parameters.get_mut("bounds") {
+                self.apply(bounds);
+            }
+            return;
+        }
+        if self.logarithmic {
+            repr.parameters
+                .insert("logarithmic".to_owned(), Parameter::Bool(true));
+        }
+        if let Some(step) = self.step {
+            repr.parameters
+                .insert("step".to_owned(), Parameter::Float(step));
+        }
+        if let Some(precision) = self.precision {
+            repr.parameters
+                .insert("precision".to_owned(), Parameter::UnsignedInt(precision));
+        }
+        if let Some(unit) = &self.unit {
+            repr.parameters
+                .insert("unit".to_owned(), Parameter::String(unit.clone()));
+        }
+    }
+}
+
+/// Another representation, with [`NumberStyle`] display options applied to it
+pub struct Styled<R> {
+    pub repr: R,
+    pub style: NumberStyle,
+}
+
+impl<R> Styled<R> {
+    pub fn new(repr: R) -> Styled<R> {
+        Styled {
+            repr,
+            style: NumberStyle::default(),
+        }
+    }
+
+    /// Display the value on a logarithmic scale.
+    pub fn logarithmic(mut self) -> Styled<R> {
+        self.style.logarithmic = true;
+        self
+    }
+
+    /// Snap values to multiples of `step`.
+    pub fn step(mut self, step: impl Into<f64>) -> Styled<R> {
+        self.style.step = Some(step.into());
+        self
+    }
+
+    /// Display values with `precision` decimal places.
+    pub fn precision(mut self, precision: u64) -> Styled<R> {
+        self.style.precision = Some(precision);
+        self
+    }
+
+    /// Display `unit` after the value.
+    pub fn unit(mut self, unit: &str) -> Styled<R> {
+        self.style.unit = Some(unit.to_owned());
+        self
+    }
+}
+
+impl<T, R> LiveModRepr<T> for Styled<R>
+where
+    R: LiveModRepr<T>,
+{
+    fn repr(&self, cur: &T) -> Namespaced<Repr> {
+        let mut repr = self.repr.repr(cur);
+        self.style.apply(&mut repr);
+        repr
+    }
+
+    fn correct(&self, value: &mut T) -> bool {
+        self.repr.correct(value)
+    }
+}
+
 /// Multiline string input
 pub struct Multiline;
 
@@ -646,6 +1137,112 @@ impl LiveModRepr<String> for Multiline {
     }
 }
 
+/// Colour picker representation for sRGB colours
+///
+/// Can be used on `[f32; 3]` and `[f32; 4]` (components in `0.0..=1.0`), `[u8; 4]`, and `u32` (packed as `0xRRGGBBAA`).
+pub struct Color;
+
+/// Colour picker representation for linear colours
+///
+/// Can be used on `[f32; 3]` and `[f32; 4]` (components in `0.0..=1.0`).
+pub struct LinearColor;
+
+macro_rules! impl_color {
+    ($(($repr:ty, $t:ty, $alpha:expr, $linear:expr, $storage:ident)),*) => {
+        $(
+            impl LiveModRepr<$t> for $repr {
+                fn repr(&self, _cur: &$t) -> Namespaced<Repr> {
+                    BuiltinRepr::Color {
+                        alpha: $alpha,
+                        linear: $linear,
+                        storage: ColorStorage::$storage,
+                    }.into()
+                }
+            }
+        )*
+    };
+}
+
+impl_color!(
+    (Color, [f32; 3], false, false, Float),
+    (Color, [f32; 4], true, false, Float),
+    (Color, [u8; 4], true, false, Byte),
+    (Color, u32, true, false, Packed),
+    (LinearColor, [f32; 3], false, true, Float),
+    (LinearColor, [f32; 4], true, true, Float)
+);
+
+/// Angle representation for values stored in radians, displayed in degrees
+pub struct Angle;
+
+impl Angle {
+    /// Wrap the angle around to stay within the given range of radians, such as `-PI..PI`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is empty or either end of it isn't finite.
+    pub fn wrapping(self, range: Range<f64>) -> WrappingAngle {
+        assert!(
+            range.start.is_finite() && range.end.is_finite() && range.start < range.end,
+            "Angle wrapping range must be finite and non-empty"
+        );
+        WrappingAngle(range)
+    }
+}
+
+/// Angle representation which wraps around to stay within a range of radians.
+///
+/// Values accepted from the viewer are wrapped by [`LiveModRepr::correct`].
+pub struct WrappingAngle(Range<f64>);
+
+impl WrappingAngle {
+    /// Wrap `radians` around into the range.
+    pub fn wrap(&self, radians: f64) -> f64 {
+        let wrapped = self.0.start + (radians - self.0.start).rem_euclid(self.0.end - self.0.start);
+        // Rounding can land exactly on the excluded end of the range
+        if wrapped >= self.0.end {
+            self.0.start
+        } else {
+            wrapped
+        }
+    }
+}
+
+macro_rules! impl_angle {
+    ($($t:ty),*) => {
+        $(
+            impl LiveModRepr<$t> for Angle {
+                fn repr(&self, _cur: &$t) -> Namespaced<Repr> {
+                    BuiltinRepr::Angle { wrap: None }.into()
+                }
+            }
+
+            impl LiveModRepr<$t> for WrappingAngle {
+                fn repr(&self, _cur: &$t) -> Namespaced<Repr> {
+                    BuiltinRepr::Angle { wrap: Some(self.0.clone()) }.into()
+                }
+
+                fn correct(&self, value: &mut $t) -> bool {
+                    if self.0.contains(&(*value as f64)) {
+                        false
+                    } else {
+                        let mut wrapped = self.wrap(*value as f64) as $t;
+                        // Converting back can round up onto the excluded end of the range
+                        if wrapped as f64 >= self.0.end {
+                            wrapped = self.0.start as $t;
+                        }
+                        let changed = wrapped != *value;
+                        *value = wrapped;
+                        changed
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_angle!(f32, f64);
+
 #[macro_export]
 macro_rules! livemod_static {
     ($($vis:vis static $name:ident : $ty:ty = $val:expr;)*) => {
@@ -664,10 +1261,13 @@ macro_rules! unsigned_primitive_impl {
                 Self::repr_static()
             }
 
-            fn accept(&mut self, target: ActionTarget, value: Parameter<Value>) -> bool {
-                debug_assert!(target.is_this());
-                *self = value.try_into_unsigned_int().unwrap() as $ty;
-                false
+            fn accept(&mut self, target: ActionTarget, value: Parameter<Value>) -> Result<bool, AcceptError> {
+                target.expect_this()?;
+                let value = value
+                    .try_into_unsigned_int()
+                    .map_err(|_| AcceptError::WrongType { expected: "an unsigned integer" })?;
+                *self = $ty::try_from(value).map_err(|_| AcceptError::OutOfRange)?;
+                Ok(false)
             }
 
             fn get_self(&self, target: ActionTarget) -> Parameter<Value> {
@@ -701,10 +1301,13 @@ macro_rules! signed_primitive_impl {
                 Self::repr_static()
             }
 
-            fn accept(&mut self, target: ActionTarget, value: Parameter<Value>) -> bool {
-                debug_assert!(target.is_this());
-                *self = value.try_into_signed_int().unwrap() as $ty;
-                false
+            fn accept(&mut self, target: ActionTarget, value: Parameter<Value>) -> Result<bool, AcceptError> {
+                target.expect_this()?;
+                let value = value
+                    .try_into_signed_int()
+                    .map_err(|_| AcceptError::WrongType { expected: "a signed integer" })?;
+                *self = $ty::try_from(value).map_err(|_| AcceptError::OutOfRange)?;
+                Ok(false)
             }
 
             fn get_self(&self, target: ActionTarget) -> Parameter<Value> {
@@ -738,10 +1341,12 @@ macro_rules! float_primitive_impl {
                 Self::repr_static()
             }
 
-            fn accept(&mut self, target: ActionTarget, value: Parameter<Value>) -> bool {
-                debug_assert!(target.is_this());
-                *self = value.try_into_float().unwrap() as $ty;
-                false
+            fn accept(&mut self, target: ActionTarget, value: Parameter<Value>) -> Result<bool, AcceptError> {
+                target.expect_this()?;
+                *self = value
+                    .try_into_float()
+                    .map_err(|_| AcceptError::WrongType { expected: "a float" })? as $ty;
+                Ok(false)
             }
 
             fn get_self(&self, target: ActionTarget) -> Parameter<Value> {
@@ -776,10 +1381,16 @@ impl LiveMod for bool {
         Self::repr_static()
     }
 
-    fn accept(&mut self, target: ActionTarget, value: Parameter<Value>) -> bool {
-        debug_assert!(target.is_this());
-        *self = value.try_into_bool().unwrap();
-        false
+    fn accept(
+        &mut self,
+        target: ActionTarget,
+        value: Parameter<Value>,
+    ) -> Result<bool, AcceptError> {
+        target.expect_this()?;
+        *self = value
+            .try_into_bool()
+            .map_err(|_| AcceptError::WrongType { expected: "a bool" })?;
+        Ok(false)
     }
 
     fn get_self(&self, target: ActionTarget) -> Parameter<Value> {
@@ -804,10 +1415,18 @@ impl LiveMod for String {
         Self::repr_static()
     }
 
-    fn accept(&mut self, target: ActionTarget, value: Parameter<Value>) -> bool {
-        debug_assert!(target.is_this());
-        *self = value.try_into_string().unwrap();
-        false
+    fn accept(
+        &mut self,
+        target: ActionTarget,
+        value: Parameter<Value>,
+    ) -> Result<bool, AcceptError> {
+        target.expect_this()?;
+        *self = value
+            .try_into_string()
+            .map_err(|_| AcceptError::WrongType {
+                expected: "a string",
+            })?;
+        Ok(false)
     }
 
     fn get_self(&self, target: ActionTarget) -> Parameter<Value> {
@@ -834,7 +1453,11 @@ where
         (**self).repr_default(target)
     }
 
-    fn accept(&mut self, target: ActionTarget, value: Parameter<Value>) -> bool {
+    fn accept(
+        &mut self,
+        target: ActionTarget,
+        value: Parameter<Value>,
+    ) -> Result<bool, AcceptError> {
         (**self).accept(target, value)
     }
 
@@ -856,12 +1479,37 @@ where
     }
 }
 
+impl<T> LiveMod for &mut T
+where
+    T: LiveMod + ?Sized,
+{
+    fn repr_default(&self, target: ActionTarget) -> Namespaced<Repr> {
+        (**self).repr_default(target)
+    }
+
+    fn accept(
+        &mut self,
+        target: ActionTarget,
+        value: Parameter<Value>,
+    ) -> Result<bool, AcceptError> {
+        (**self).accept(target, value)
+    }
+
+    fn get_self(&self, target: ActionTarget) -> Parameter<Value> {
+        (**self).get_self(target)
+    }
+}
+
 impl LiveMod for Box<dyn LiveMod> {
     fn repr_default(&self, target: ActionTarget) -> Namespaced<Repr> {
         (**self).repr_default(target)
     }
 
-    fn accept(&mut self, target: ActionTarget, value: Parameter<Value>) -> bool {
+    fn accept(
+        &mut self,
+        target: ActionTarget,
+        value: Parameter<Value>,
+    ) -> Result<bool, AcceptError> {
         (**self).accept(target, value)
     }
 
@@ -870,244 +1518,623 @@ impl LiveMod for Box<dyn LiveMod> {
     }
 }
 
-impl<T> LiveMod for Vec<T>
+impl<T, const N: usize> LiveMod for [T; N]
 where
-    T: LiveMod + Default,
+    T: LiveMod,
 {
     fn repr_default(&self, target: ActionTarget) -> Namespaced<Repr> {
         if let Some((field, field_target)) = target.strip_one_field() {
-            self[field.parse::<usize>().unwrap()].repr_default(field_target)
+            match element(self, field) {
+                Some(element) => element.repr_default(field_target),
+                None => Namespaced::unknown_field_repr(),
+            }
         } else {
-            Namespaced {
-                name: vec!["livemod".to_owned(), "vec".to_owned()],
-                parameters: self
+            Namespaced::basic_structure_repr(
+                &format!("[_; {}]", N),
+                &self
                     .iter()
                     .enumerate()
-                    .map(|(i, v)| {
-                        (
-                            format!("{}", i),
-                            Parameter::Namespaced(v.repr_default(ActionTarget::This)),
-                        )
-                    })
-                    .chain(std::iter::once((
-                        "len".to_owned(),
-                        Parameter::UnsignedInt(self.len() as u64),
-                    )))
-                    .collect(),
-                _marker: std::marker::PhantomData,
-            }
+                    .map(|(i, v)| (format!("{}", i), v.repr_default(ActionTarget::This)))
+                    .collect::<Vec<_>>(),
+            )
         }
     }
 
-    fn accept(&mut self, target: ActionTarget, value: Parameter<Value>) -> bool {
+    fn accept(
+        &mut self,
+        target: ActionTarget,
+        value: Parameter<Value>,
+    ) -> Result<bool, AcceptError> {
         if let Some((field, field_target)) = target.strip_one_field() {
-            if field == "len" {
-                debug_assert!(field_target.is_this());
-                let len = value.try_into_unsigned_int().unwrap() as usize;
-                if len != self.len() {
-                    self.resize_with(len, Default::default);
-                    true
-                } else {
-                    false
-                }
-            } else {
-                self[field.parse::<usize>().unwrap()].accept(field_target, value)
-            }
+            element_mut(self, field)?.accept(field_target, value)
         } else {
-            let trigger = value.try_into_namespaced().unwrap();
-            if trigger.name[2] == "rm" {
-                let index = trigger.parameters["idx"]
-                    .clone()
-                    .try_into_unsigned_int()
-                    .unwrap() as usize;
-                self.remove(index);
-            } else if trigger.name[2] == "swp" {
-                let idx_a = trigger.parameters["a"]
-                    .clone()
-                    .try_into_unsigned_int()
-                    .unwrap() as usize;
-                let idx_b = trigger.parameters["b"]
-                    .clone()
-                    .try_into_unsigned_int()
-                    .unwrap() as usize;
-                self.swap(idx_a, idx_b);
-            }
-            true
+            Err(AcceptError::MissingField)
         }
     }
 
     fn get_self(&self, target: ActionTarget) -> Parameter<Value> {
         if let Some((field, field_target)) = target.strip_one_field() {
-            self[field.parse::<usize>().unwrap()].get_self(field_target)
+            match element(self, field) {
+                Some(element) => element.get_self(field_target),
+                None => Namespaced::unknown_field_value(),
+            }
         } else {
-            Parameter::Namespaced(Namespaced {
-                name: vec!["livemod".to_owned(), "vec".to_owned()],
-                parameters: self
+            Parameter::Namespaced(Namespaced::basic_structure_value(
+                &self
                     .iter()
                     .enumerate()
                     .map(|(i, v)| (format!("{}", i), v.get_self(ActionTarget::This)))
-                    .collect(),
-                _marker: std::marker::PhantomData,
-            })
+                    .collect::<Vec<_>>(),
+            ))
         }
     }
 }
 
-impl<K, V> LiveMod for std::collections::HashMap<K, V>
+impl<T> LiveMod for Vec<T>
 where
-    K: LiveModCtor + Eq + Hash + std::fmt::Debug,
-    V: LiveMod + Default,
+    T: LiveMod + Default,
 {
     fn repr_default(&self, target: ActionTarget) -> Namespaced<Repr> {
-        if let Some((field, field_target)) = target.strip_one_field() {
-            // Field will be "keys" or "values"
-            match field {
-                "keys" => {
-                    // Repr of a key will always be the same
-                    K::repr_static()
-                }
-                "values" => {
-                    if let Some((field, field_target)) = field_target.strip_one_field() {
-                        self.get(&K::from_value(Parameter::deserialize(&mut field.bytes()).unwrap()).unwrap())
-                            .unwrap()
-                            .repr_default(field_target)
-                    } else {
-                        unimplemented!()
-                    }
-                }
-                _ => unimplemented!(),
+        vec_repr_default(self, target)
+    }
+
+    fn accept(
+        &mut self,
+        target: ActionTarget,
+        value: Parameter<Value>,
+    ) -> Result<bool, AcceptError> {
+        accept_vec(self, target, value, Default::default)
+    }
+
+    fn get_self(&self, target: ActionTarget) -> Parameter<Value> {
+        vec_get_self(self, target)
+    }
+}
+
+fn vec_repr_default<T: LiveMod>(vec: &[T], target: ActionTarget) -> Namespaced<Repr> {
+    if let Some((field, field_target)) = target.strip_one_field() {
+        match element(vec, field) {
+            Some(element) => element.repr_default(field_target),
+            None => Namespaced::unknown_field_repr(),
+        }
+    } else {
+        Namespaced {
+            name: vec!["livemod".to_owned(), "vec".to_owned()],
+            parameters: vec
+                .iter()
+                .enumerate()
+                .map(|(i, v)| {
+                    (
+                        format!("{}", i),
+                        Parameter::Namespaced(v.repr_default(ActionTarget::This)),
+                    )
+                })
+                .chain(std::iter::once((
+                    "len".to_owned(),
+                    Parameter::UnsignedInt(vec.len() as u64),
+                )))
+                .collect(),
+            _marker: std::marker::PhantomData,
+        }
+    }
+}
+
+/// Accept a value sent to a vector, using `new_element` to create the elements added when the vector is grown or an
+/// element is inserted.
+fn accept_vec<T: LiveMod>(
+    vec: &mut Vec<T>,
+    target: ActionTarget,
+    value: Parameter<Value>,
+    mut new_element: impl FnMut() -> T,
+) -> Result<bool, AcceptError> {
+    if let Some((field, field_target)) = target.strip_one_field() {
+        if field == "len" {
+            field_target.expect_this()?;
+            let len = value
+                .try_into_unsigned_int()
+                .map_err(|_| AcceptError::WrongType {
+                    expected: "a length",
+                })?;
+            let len = usize::try_from(len).map_err(|_| AcceptError::OutOfRange)?;
+            if len != vec.len() {
+                vec.resize_with(len, new_element);
+                Ok(true)
+            } else {
+                Ok(false)
             }
         } else {
-            Namespaced {
-                name: vec!["livemod".to_owned(), "map".to_owned()],
-                parameters: IntoIter::new([
-                    ("key".to_owned(), Parameter::Namespaced(K::repr_static())),
-                    (
-                        "keys".to_owned(),
-                        Parameter::Namespaced(Namespaced {
-                            name: vec!["livemod".to_owned(), "fields".to_owned()],
-                            parameters: self
-                                .iter()
-                                .map(|(k, _v)| {
-                                    (
-                                        k.get_self(ActionTarget::This).serialize(),
-                                        Parameter::Namespaced(k.repr_default(ActionTarget::This)),
-                                    )
-                                })
-                                .collect(),
-                            _marker: std::marker::PhantomData,
-                        }),
-                    ),
-                    (
-                        "values".to_owned(),
-                        Parameter::Namespaced(Namespaced {
-                            name: vec!["livemod".to_owned(), "fields".to_owned()],
-                            parameters: self
-                                .iter()
-                                .map(|(k, v)| {
-                                    (
-                                        k.get_self(ActionTarget::This).serialize(),
-                                        Parameter::Namespaced(v.repr_default(ActionTarget::This)),
-                                    )
-                                })
-                                .collect(),
-                            _marker: std::marker::PhantomData,
-                        }),
-                    ),
-                ])
+            element_mut(vec, field)?.accept(field_target, value)
+        }
+    } else {
+        let trigger = trigger_name(value)?;
+        if trigger.name[2] == "rm" {
+            let index = trigger_index(&trigger, "idx", vec.len())?;
+            vec.remove(index);
+        } else if trigger.name[2] == "swp" {
+            let idx_a = trigger_index(&trigger, "a", vec.len())?;
+            let idx_b = trigger_index(&trigger, "b", vec.len())?;
+            vec.swap(idx_a, idx_b);
+        } else if trigger.name[2] == "insert" {
+            // Elements can also be inserted at the end
+            let index = trigger_index(&trigger, "idx", vec.len() + 1)?;
+            vec.insert(index, new_element());
+        } else if trigger.name[2] == "mv" {
+            let from = trigger_index(&trigger, "from", vec.len())?;
+            let to = trigger_index(&trigger, "to", vec.len())?;
+            let element = vec.remove(from);
+            vec.insert(to, element);
+        } else {
+            return Err(AcceptError::WrongType {
+                expected: "a trigger",
+            });
+        }
+        Ok(true)
+    }
+}
+
+fn vec_get_self<T: LiveMod>(vec: &[T], target: ActionTarget) -> Parameter<Value> {
+    if let Some((field, field_target)) = target.strip_one_field() {
+        match element(vec, field) {
+            Some(element) => element.get_self(field_target),
+            None => Namespaced::unknown_field_value(),
+        }
+    } else {
+        Parameter::Namespaced(Namespaced {
+            name: vec!["livemod".to_owned(), "vec".to_owned()],
+            parameters: vec
+                .iter()
+                .enumerate()
+                .map(|(i, v)| (format!("{}", i), v.get_self(ActionTarget::This)))
                 .collect(),
-                _marker: std::marker::PhantomData,
+            _marker: std::marker::PhantomData,
+        })
+    }
+}
+
+impl<K, V> LiveMod for std::collections::HashMap<K, V>
+where
+    K: LiveModCtor + Eq + Hash + std::fmt::Debug,
+    V: LiveMod + Default,
+{
+    fn repr_default(&self, target: ActionTarget) -> Namespaced<Repr> {
+        map_repr_default(self, target)
+    }
+
+    fn accept(
+        &mut self,
+        target: ActionTarget,
+        value: Parameter<Value>,
+    ) -> Result<bool, AcceptError> {
+        accept_map(self, target, value, Default::default)
+    }
+
+    fn get_self(&self, target: ActionTarget) -> Parameter<Value> {
+        map_get_self(self, target)
+    }
+}
+
+fn map_repr_default<K, V>(
+    map: &std::collections::HashMap<K, V>,
+    target: ActionTarget,
+) -> Namespaced<Repr>
+where
+    K: LiveModCtor + Eq + Hash,
+    V: LiveMod,
+{
+    if let Some((field, field_target)) = target.strip_one_field() {
+        // Field will be "keys" or "values"
+        match field {
+            "keys" => {
+                // Repr of a key will always be the same
+                K::repr_static()
             }
+            "values" => match field_target
+                .strip_one_field()
+                .and_then(|(field, field_target)| Some((map_value(map, field)?, field_target)))
+            {
+                Some((value, field_target)) => value.repr_default(field_target),
+                None => Namespaced::unknown_field_repr(),
+            },
+            _ => Namespaced::unknown_field_repr(),
+        }
+    } else {
+        Namespaced {
+            name: vec!["livemod".to_owned(), "map".to_owned()],
+            parameters: IntoIterator::into_iter([
+                ("key".to_owned(), Parameter::Namespaced(K::repr_static())),
+                (
+                    "keys".to_owned(),
+                    Parameter::Namespaced(Namespaced {
+                        name: vec!["livemod".to_owned(), "fields".to_owned()],
+                        parameters: map
+                            .keys()
+                            .map(|k| {
+                                (
+                                    key_name(&k.get_self(ActionTarget::This)),
+                                    Parameter::Namespaced(k.repr_default(ActionTarget::This)),
+                                )
+                            })
+                            .collect(),
+                        _marker: std::marker::PhantomData,
+                    }),
+                ),
+                (
+                    "values".to_owned(),
+                    Parameter::Namespaced(Namespaced {
+                        name: vec!["livemod".to_owned(), "fields".to_owned()],
+                        parameters: map
+                            .iter()
+                            .map(|(k, v)| {
+                                (
+                                    key_name(&k.get_self(ActionTarget::This)),
+                                    Parameter::Namespaced(v.repr_default(ActionTarget::This)),
+                                )
+                            })
+                            .collect(),
+                        _marker: std::marker::PhantomData,
+                    }),
+                ),
+            ])
+            .collect(),
+            _marker: std::marker::PhantomData,
         }
     }
+}
 
-    fn accept(&mut self, target: ActionTarget, value: Parameter<Value>) -> bool {
-        if let Some((field, field_target)) = target.strip_one_field() {
-            // As with repr_default, will be "keys" or "values"
-            match field {
-                "keys" => {
-                    if let Some((field, field_target)) = field_target.strip_one_field() {
-                        let (mut k, v) = self
-                            .remove_entry(
-                                &K::from_value(Parameter::deserialize(&mut field.bytes()).unwrap()).unwrap(),
-                            )
-                            .unwrap();
-                        k.accept(field_target, value);
-                        self.insert(k, v);
-                        true
-                    } else {
-                        unimplemented!()
+/// Accept a value sent to a map, using `new_value` to create the values of the entries added from the viewer.
+fn accept_map<K, V>(
+    map: &mut std::collections::HashMap<K, V>,
+    target: ActionTarget,
+    value: Parameter<Value>,
+    mut new_value: impl FnMut() -> V,
+) -> Result<bool, AcceptError>
+where
+    K: LiveModCtor + Eq + Hash,
+    V: LiveMod,
+{
+    if let Some((field, field_target)) = target.strip_one_field() {
+        // As with repr_default, will be "keys" or "values"
+        match field {
+            "keys" => {
+                if let Some((field, field_target)) = field_target.strip_one_field() {
+                    let key = parse_key_name(field)
+                        .and_then(K::from_value)
+                        .ok_or_else(|| AcceptError::UnknownField(field.to_owned()))?;
+                    let (mut k, v) = map
+                        .remove_entry(&key)
+                        .ok_or_else(|| AcceptError::UnknownField(field.to_owned()))?;
+                    match k.accept(field_target, value) {
+                        Ok(_) if !map.contains_key(&k) => {
+                            map.insert(k, v);
+                            Ok(true)
+                        }
+                        result => {
+                            // Put the entry back under its old key, rather than replacing another entry
+                            map.insert(key, v);
+                            result.and(Err(AcceptError::DuplicateKey))
+                        }
                     }
+                } else {
+                    Err(AcceptError::MissingField)
                 }
-                "values" => {
-                    if let Some((field, field_target)) = field_target.strip_one_field() {
-                        self.get_mut(
-                            &K::from_value(Parameter::deserialize(&mut field.bytes()).unwrap()).unwrap(),
-                        )
-                        .unwrap()
+            }
+            "values" => {
+                if let Some((field, field_target)) = field_target.strip_one_field() {
+                    parse_key_name(field)
+                        .and_then(K::from_value)
+                        .and_then(|key| map.get_mut(&key))
+                        .ok_or_else(|| AcceptError::UnknownField(field.to_owned()))?
                         .accept(field_target, value)
-                    } else {
-                        unimplemented!()
-                    }
+                } else {
+                    Err(AcceptError::MissingField)
+                }
+            }
+            _ => Err(AcceptError::UnknownField(field.to_owned())),
+        }
+    } else {
+        let trigger = trigger_name(value)?;
+        let key = || {
+            trigger
+                .parameters
+                .get("key")
+                .cloned()
+                .and_then(K::from_value)
+                .ok_or(AcceptError::WrongType { expected: "a key" })
+        };
+        if trigger.name[2] == "rm" {
+            map.remove(&key()?);
+        } else if trigger.name[2] == "insert" {
+            let key = key()?;
+            if map.contains_key(&key) {
+                return Err(AcceptError::DuplicateKey);
+            }
+            map.insert(key, new_value());
+        } else {
+            return Err(AcceptError::WrongType {
+                expected: "a trigger",
+            });
+        }
+        Ok(true)
+    }
+}
+
+fn map_get_self<K, V>(
+    map: &std::collections::HashMap<K, V>,
+    target: ActionTarget,
+) -> Parameter<Value>
+where
+    K: LiveModCtor + Eq + Hash,
+    V: LiveMod,
+{
+    if let Some((field, field_target)) = target.strip_one_field() {
+        // As with repr_default, will be "keys" or "values"
+        let entry = field_target
+            .strip_one_field()
+            .and_then(|(key, field_target)| {
+                let (key, value) =
+                    map.get_key_value(&parse_key_name(key).and_then(K::from_value)?)?;
+                Some((key, value, field_target))
+            });
+        match (field, entry) {
+            ("keys", Some((key, _, field_target))) => key.get_self(field_target),
+            ("values", Some((_, value, field_target))) => value.get_self(field_target),
+            _ => Namespaced::unknown_field_value(),
+        }
+    } else {
+        Parameter::Namespaced(Namespaced {
+            name: vec!["livemod".to_owned(), "map".to_owned()],
+            parameters: IntoIterator::into_iter([
+                (
+                    "keys".to_owned(),
+                    Parameter::Namespaced(Namespaced {
+                        name: vec!["livemod".to_owned(), "fields".to_owned()],
+                        parameters: map
+                            .keys()
+                            .map(|k| {
+                                let val = k.get_self(ActionTarget::This);
+                                (key_name(&val), val)
+                            })
+                            .collect(),
+                        _marker: std::marker::PhantomData,
+                    }),
+                ),
+                (
+                    "values".to_owned(),
+                    Parameter::Namespaced(Namespaced {
+                        name: vec!["livemod".to_owned(), "fields".to_owned()],
+                        parameters: map
+                            .iter()
+                            .map(|(k, v)| {
+                                (
+                                    key_name(&k.get_self(ActionTarget::This)),
+                                    v.get_self(ActionTarget::This),
+                                )
+                            })
+                            .collect(),
+                        _marker: std::marker::PhantomData,
+                    }),
+                ),
+            ])
+            .collect(),
+            _marker: std::marker::PhantomData,
+        })
+    }
+}
+
+/// Name the entry for a map key in a way which can be used as part of a path.
+///
+/// Serialized values can contain the separators used by paths and the message format, so these are percent-encoded.
+fn key_name(key: &Parameter<Value>) -> String {
+    let mut name = String::new();
+    for c in key.serialize().chars() {
+        match c {
+            '%' | '.' | ';' | '=' | '{' | '}' => name.push_str(&format!("%{:02X}", c as u8)),
+            c => name.push(c),
+        }
+    }
+    name
+}
+
+/// Reverse the encoding done by [`key_name`].
+fn parse_key_name(name: &str) -> Option<Parameter<Value>> {
+    let mut bytes = Vec::with_capacity(name.len());
+    let mut iter = name.bytes();
+    while let Some(b) = iter.next() {
+        if b == b'%' {
+            let hex = [iter.next()?, iter.next()?];
+            bytes.push(u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?);
+        } else {
+            bytes.push(b);
+        }
+    }
+    Parameter::deserialize(&mut bytes.into_iter()).ok()
+}
+
+/// Get the value of the map entry named by a field of an [`ActionTarget`].
+fn map_value<'a, K, V>(map: &'a std::collections::HashMap<K, V>, field: &str) -> Option<&'a V>
+where
+    K: LiveModCtor + Eq + Hash,
+{
+    map.get(&parse_key_name(field).and_then(K::from_value)?)
+}
+
+/// Get the element of a slice named by a field of an [`ActionTarget`].
+fn element<'a, T>(slice: &'a [T], field: &str) -> Option<&'a T> {
+    slice.get(field.parse::<usize>().ok()?)
+}
+
+/// Get the element of a slice named by a field of an [`ActionTarget`].
+fn element_mut<'a, T>(slice: &'a mut [T], field: &str) -> Result<&'a mut T, AcceptError> {
+    field
+        .parse::<usize>()
+        .ok()
+        .and_then(move |index| slice.get_mut(index))
+        .ok_or_else(|| AcceptError::UnknownField(field.to_owned()))
+}
+
+/// Unwrap a trigger sent by the viewer to a collection, checking that it has a name such as `livemod:trigger:rm`.
+fn trigger_name(value: Parameter<Value>) -> Result<Namespaced<Value>, AcceptError> {
+    match value.try_into_namespaced() {
+        Ok(trigger) if trigger.name.len() > 2 => Ok(trigger),
+        _ => Err(AcceptError::WrongType {
+            expected: "a trigger",
+        }),
+    }
+}
+
+/// Get an index parameter of a trigger, checking that it is less than `len`.
+fn trigger_index(
+    trigger: &Namespaced<Value>,
+    name: &str,
+    len: usize,
+) -> Result<usize, AcceptError> {
+    let index = trigger
+        .parameters
+        .get(name)
+        .and_then(|index| index.as_unsigned_int())
+        .ok_or(AcceptError::WrongType {
+            expected: "an index",
+        })?;
+    match usize::try_from(*index) {
+        Ok(index) if index < len => Ok(index),
+        _ => Err(AcceptError::OutOfRange),
+    }
+}
+
+fn range_bound<'a, T>(start: &'a T, end: &'a T, field: &str) -> Option<&'a T> {
+    match field {
+        "start" => Some(start),
+        "end" => Some(end),
+        _ => None,
+    }
+}
+
+/// Update one bound of a range, dragging the other bound along with it if the range would otherwise be reversed.
+fn accept_range_bound<T>(
+    start: &mut T,
+    end: &mut T,
+    target: ActionTarget,
+    value: Parameter<Value>,
+) -> Result<bool, AcceptError>
+where
+    T: LiveMod + PartialOrd + Clone,
+{
+    if let Some((field, field_target)) = target.strip_one_field() {
+        match field {
+            "start" => {
+                let repr_changed = start.accept(field_target, value)?;
+                if *start > *end {
+                    *end = start.clone();
+                    Ok(true)
+                } else {
+                    Ok(repr_changed)
+                }
+            }
+            "end" => {
+                let repr_changed = end.accept(field_target, value)?;
+                if *end < *start {
+                    *start = end.clone();
+                    Ok(true)
+                } else {
+                    Ok(repr_changed)
                 }
-                _ => unimplemented!(),
+            }
+            _ => Err(AcceptError::UnknownField(field.to_owned())),
+        }
+    } else {
+        Err(AcceptError::MissingField)
+    }
+}
+
+fn range_value<T: LiveMod>(start: &T, end: &T) -> Parameter<Value> {
+    Parameter::Namespaced(Namespaced {
+        name: vec!["livemod".to_owned(), "range".to_owned()],
+        parameters: LinkedHashMap::from_iter(IntoIterator::into_iter([
+            ("start".to_owned(), start.get_self(ActionTarget::This)),
+            ("end".to_owned(), end.get_self(ActionTarget::This)),
+        ])),
+        _marker: std::marker::PhantomData,
+    })
+}
+
+impl<T> LiveMod for Range<T>
+where
+    T: LiveMod + PartialOrd + Clone,
+{
+    fn repr_default(&self, target: ActionTarget) -> Namespaced<Repr> {
+        if let Some((field, field_target)) = target.strip_one_field() {
+            match range_bound(&self.start, &self.end, field) {
+                Some(bound) => bound.repr_default(field_target),
+                None => Namespaced::unknown_field_repr(),
             }
         } else {
-            let trigger = value.try_into_namespaced().unwrap();
-            if trigger.name[2] == "rm" {
-                let key = K::from_value(trigger.parameters["key"].clone()).unwrap();
-                self.remove(&key);
-            } else if trigger.name[2] == "insert" {
-                let key = K::from_value(trigger.parameters["key"].clone()).unwrap();
-                self.insert(key, Default::default());
+            BuiltinRepr::Range {
+                inclusive: false,
+                bounds: self.start.repr_default(ActionTarget::This),
             }
-            true
+            .into()
         }
     }
 
+    fn accept(
+        &mut self,
+        target: ActionTarget,
+        value: Parameter<Value>,
+    ) -> Result<bool, AcceptError> {
+        accept_range_bound(&mut self.start, &mut self.end, target, value)
+    }
+
     fn get_self(&self, target: ActionTarget) -> Parameter<Value> {
         if let Some((field, field_target)) = target.strip_one_field() {
-            self.get(&K::from_value(Parameter::deserialize(&mut field.bytes()).unwrap()).unwrap())
-                .unwrap()
-                .get_self(field_target)
+            match range_bound(&self.start, &self.end, field) {
+                Some(bound) => bound.get_self(field_target),
+                None => Namespaced::unknown_field_value(),
+            }
         } else {
-            Parameter::Namespaced(Namespaced {
-                name: vec!["livemod".to_owned(), "map".to_owned()],
-                parameters: IntoIter::new([
-                    (
-                        "keys".to_owned(),
-                        Parameter::Namespaced(Namespaced {
-                            name: vec!["livemod".to_owned(), "fields".to_owned()],
-                            parameters: self
-                                .iter()
-                                .map(|(k, _v)| {
-                                    let val = k.get_self(ActionTarget::This);
-                                    (val.serialize(), val)
-                                })
-                                .collect(),
-                            _marker: std::marker::PhantomData,
-                        }),
-                    ),
-                    (
-                        "values".to_owned(),
-                        Parameter::Namespaced(Namespaced {
-                            name: vec!["livemod".to_owned(), "fields".to_owned()],
-                            parameters: self
-                                .iter()
-                                .map(|(k, v)| {
-                                    (
-                                        k.get_self(ActionTarget::This).serialize(),
-                                        v.get_self(ActionTarget::This),
-                                    )
-                                })
-                                .collect(),
-                            _marker: std::marker::PhantomData,
-                        }),
-                    ),
-                ])
-                .collect(),
-                _marker: std::marker::PhantomData,
-            })
+            range_value(&self.start, &self.end)
+        }
+    }
+}
+
+impl<T> LiveMod for RangeInclusive<T>
+where
+    T: LiveMod + PartialOrd + Clone,
+{
+    fn repr_default(&self, target: ActionTarget) -> Namespaced<Repr> {
+        if let Some((field, field_target)) = target.strip_one_field() {
+            match range_bound(self.start(), self.end(), field) {
+                Some(bound) => bound.repr_default(field_target),
+                None => Namespaced::unknown_field_repr(),
+            }
+        } else {
+            BuiltinRepr::Range {
+                inclusive: true,
+                bounds: self.start().repr_default(ActionTarget::This),
+            }
+            .into()
+        }
+    }
+
+    fn accept(
+        &mut self,
+        target: ActionTarget,
+        value: Parameter<Value>,
+    ) -> Result<bool, AcceptError> {
+        // `RangeInclusive` doesn't allow modifying its bounds in place
+        let (mut start, mut end) = self.clone().into_inner();
+        let repr_changed = accept_range_bound(&mut start, &mut end, target, value)?;
+        *self = start..=end;
+        Ok(repr_changed)
+    }
+
+    fn get_self(&self, target: ActionTarget) -> Parameter<Value> {
+        if let Some((field, field_target)) = target.strip_one_field() {
+            match range_bound(self.start(), self.end(), field) {
+                Some(bound) => bound.get_self(field_target),
+                None => Namespaced::unknown_field_value(),
+            }
+        } else {
+            range_value(self.start(), self.end())
         }
     }
 }
@@ -1133,11 +2160,21 @@ impl<A: Send, F: FnMut(&mut A) + Send> LiveMod for TriggerFn<A, F> {
         }
     }
 
-    fn accept(&mut self, target: ActionTarget, trigger: Parameter<Value>) -> bool {
-        debug_assert!(target.is_this());
-        debug_assert!(trigger.try_into_namespaced().unwrap().name[1] == "trigger");
-        (self.func)(&mut self.arg);
-        false
+    fn accept(
+        &mut self,
+        target: ActionTarget,
+        trigger: Parameter<Value>,
+    ) -> Result<bool, AcceptError> {
+        target.expect_this()?;
+        match trigger.try_into_namespaced() {
+            Ok(trigger) if trigger.name == ["livemod", "trigger"] => {
+                (self.func)(&mut self.arg);
+                Ok(false)
+            }
+            _ => Err(AcceptError::WrongType {
+                expected: "a trigger",
+            }),
+        }
     }
 
     fn get_self(&self, target: ActionTarget) -> Parameter<Value> {
@@ -1149,3 +2186,517 @@ impl<A: Send, F: FnMut(&mut A) + Send> LiveMod for TriggerFn<A, F> {
         })
     }
 }
+
+/// A wrapper around an enum which remembers the last value of each variant, so that selecting a variant again in the
+/// viewer restores the values of its fields, instead of resetting them to their defaults.
+///
+/// The restored variant is then selected again with [`LiveMod::accept`], so that the enum's `on_change` hook runs. Enums
+/// deriving `LiveMod` keep the values of the current variant's fields when it is selected again.
+#[derive(Clone, Debug, Default)]
+pub struct Stashed<T> {
+    value: T,
+    stash: std::collections::HashMap<String, T>,
+}
+
+impl<T> Stashed<T> {
+    pub fn new(value: T) -> Stashed<T> {
+        Stashed {
+            value,
+            stash: std::collections::HashMap::new(),
+        }
+    }
+
+    /// Discard the values of the other variants, and return the current value.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> std::ops::Deref for Stashed<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> std::ops::DerefMut for Stashed<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+impl<T> LiveMod for Stashed<T>
+where
+    T: LiveMod + Clone,
+{
+    fn repr_default(&self, target: ActionTarget) -> Namespaced<Repr> {
+        self.value.repr_default(target)
+    }
+
+    fn accept(
+        &mut self,
+        target: ActionTarget,
+        value: Parameter<Value>,
+    ) -> Result<bool, AcceptError> {
+        if let ActionTarget::Field(["variant"]) = target {
+            let current = self
+                .value
+                .get_self(ActionTarget::This)
+                .try_into_namespaced()
+                .ok()
+                .and_then(|value| value.parameters.get("variant")?.as_string().cloned());
+            if let (Some(current), Some(selected)) = (current, value.as_string()) {
+                if current != *selected {
+                    let selected = selected.clone();
+                    let previous = match self.stash.remove(&selected) {
+                        Some(stashed) => {
+                            let previous = std::mem::replace(&mut self.value, stashed);
+                            if let Err(err) = self.value.accept(target, value) {
+                                // Put the current variant back rather than leaving the stashed one live
+                                let stashed = std::mem::replace(&mut self.value, previous);
+                                self.stash.insert(selected, stashed);
+                                return Err(err);
+                            }
+                            previous
+                        }
+                        None => {
+                            let previous = self.value.clone();
+                            self.value.accept(target, value)?;
+                            previous
+                        }
+                    };
+                    self.stash.insert(current, previous);
+                    return Ok(true);
+                }
+            }
+        }
+        self.value.accept(target, value)
+    }
+
+    fn get_self(&self, target: ActionTarget) -> Parameter<Value> {
+        self.value.get_self(target)
+    }
+}
+
+/// A wrapper around a vector which creates the elements added from the viewer with `new_element`, so that the element
+/// type doesn't need to implement `Default`.
+#[derive(Clone)]
+pub struct VecWith<T, F> {
+    value: Vec<T>,
+    new_element: F,
+}
+
+impl<T, F: FnMut() -> T> VecWith<T, F> {
+    pub fn new(value: Vec<T>, new_element: F) -> VecWith<T, F> {
+        VecWith { value, new_element }
+    }
+
+    pub fn into_inner(self) -> Vec<T> {
+        self.value
+    }
+}
+
+impl<T, F> std::ops::Deref for VecWith<T, F> {
+    type Target = Vec<T>;
+
+    fn deref(&self) -> &Vec<T> {
+        &self.value
+    }
+}
+
+impl<T, F> std::ops::DerefMut for VecWith<T, F> {
+    fn deref_mut(&mut self) -> &mut Vec<T> {
+        &mut self.value
+    }
+}
+
+impl<T, F> LiveMod for VecWith<T, F>
+where
+    T: LiveMod,
+    F: FnMut() -> T + Send,
+{
+    fn repr_default(&self, target: ActionTarget) -> Namespaced<Repr> {
+        vec_repr_default(&self.value, target)
+    }
+
+    fn accept(
+        &mut self,
+        target: ActionTarget,
+        value: Parameter<Value>,
+    ) -> Result<bool, AcceptError> {
+        accept_vec(&mut self.value, target, value, &mut self.new_element)
+    }
+
+    fn get_self(&self, target: ActionTarget) -> Parameter<Value> {
+        vec_get_self(&self.value, target)
+    }
+}
+
+/// A wrapper around a map which creates the values of the entries added from the viewer with `new_value`, so that the
+/// value type doesn't need to implement `Default`.
+#[derive(Clone)]
+pub struct MapWith<K, V, F> {
+    value: std::collections::HashMap<K, V>,
+    new_value: F,
+}
+
+impl<K, V, F: FnMut() -> V> MapWith<K, V, F> {
+    pub fn new(value: std::collections::HashMap<K, V>, new_value: F) -> MapWith<K, V, F> {
+        MapWith { value, new_value }
+    }
+
+    pub fn into_inner(self) -> std::collections::HashMap<K, V> {
+        self.value
+    }
+}
+
+impl<K, V, F> std::ops::Deref for MapWith<K, V, F> {
+    type Target = std::collections::HashMap<K, V>;
+
+    fn deref(&self) -> &std::collections::HashMap<K, V> {
+        &self.value
+    }
+}
+
+impl<K, V, F> std::ops::DerefMut for MapWith<K, V, F> {
+    fn deref_mut(&mut self) -> &mut std::collections::HashMap<K, V> {
+        &mut self.value
+    }
+}
+
+impl<K, V, F> LiveMod for MapWith<K, V, F>
+where
+    K: LiveModCtor + Eq + Hash,
+    V: LiveMod,
+    F: FnMut() -> V + Send,
+{
+    fn repr_default(&self, target: ActionTarget) -> Namespaced<Repr> {
+        map_repr_default(&self.value, target)
+    }
+
+    fn accept(
+        &mut self,
+        target: ActionTarget,
+        value: Parameter<Value>,
+    ) -> Result<bool, AcceptError> {
+        accept_map(&mut self.value, target, value, &mut self.new_value)
+    }
+
+    fn get_self(&self, target: ActionTarget) -> Parameter<Value> {
+        map_get_self(&self.value, target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trigger(name: &str, parameters: &[(&str, u64)]) -> Parameter<Value> {
+        Parameter::Namespaced(Namespaced::new(
+            vec!["livemod".to_owned(), "trigger".to_owned(), name.to_owned()],
+            parameters
+                .iter()
+                .map(|(name, value)| ((*name).to_owned(), Parameter::UnsignedInt(*value)))
+                .collect(),
+        ))
+    }
+
+    #[test]
+    fn key_names_round_trip() {
+        let keys: Vec<Parameter<Value>> = vec![
+            Parameter::UnsignedInt(42),
+            Parameter::SignedInt(-7),
+            Parameter::Bool(true),
+            Parameter::String("a.b;c=d{e}%f".to_owned()),
+            Parameter::Namespaced(Namespaced::basic_structure_value(&[(
+                "x".to_owned(),
+                Parameter::Float(1.5),
+            )])),
+        ];
+        for key in keys {
+            let name = key_name(&key);
+            assert!(!name.contains(&['.', ';', '=', '{', '}'][..]), "{}", name);
+            let parsed = parse_key_name(&name).expect("key name should parse");
+            assert_eq!(parsed.serialize(), key.serialize());
+        }
+        assert!(parse_key_name("s3-ab%").is_none());
+        assert!(parse_key_name("%ZZ").is_none());
+    }
+
+    #[test]
+    fn deserialize_rejects_malformed_input() {
+        let parse = |s: &str| Parameter::<Value>::deserialize(&mut s.bytes()).map(|_| ());
+        assert_eq!(parse(""), Err(DeserializeError::UnexpectedEOF));
+        assert_eq!(parse("u-;"), Err(DeserializeError::InvalidNumber));
+        assert_eq!(parse("sx-;"), Err(DeserializeError::InvalidNumber));
+        assert_eq!(parse("x;"), Err(DeserializeError::InvalidParameter(b'x')));
+        assert_eq!(parse("u12;"), Ok(()));
+    }
+
+    #[test]
+    fn vec_triggers() {
+        let mut vec = vec![1u32, 2, 3];
+        assert_eq!(
+            vec.accept(ActionTarget::This, trigger("insert", &[("idx", 3)])),
+            Ok(true)
+        );
+        assert_eq!(vec, [1, 2, 3, 0]);
+        assert_eq!(
+            vec.accept(ActionTarget::This, trigger("rm", &[("idx", 0)])),
+            Ok(true)
+        );
+        assert_eq!(vec, [2, 3, 0]);
+        assert_eq!(
+            vec.accept(ActionTarget::This, trigger("mv", &[("from", 0), ("to", 2)])),
+            Ok(true)
+        );
+        assert_eq!(vec, [3, 0, 2]);
+        assert_eq!(
+            vec.accept(ActionTarget::This, trigger("swp", &[("a", 0), ("b", 1)])),
+            Ok(true)
+        );
+        assert_eq!(vec, [0, 3, 2]);
+        assert_eq!(
+            vec.accept(ActionTarget::Field(&["len"]), Parameter::UnsignedInt(1)),
+            Ok(true)
+        );
+        assert_eq!(vec, [0]);
+    }
+
+    #[test]
+    fn vec_triggers_out_of_bounds() {
+        let mut vec = vec![1u32, 2];
+        assert_eq!(
+            vec.accept(ActionTarget::This, trigger("insert", &[("idx", 3)])),
+            Err(AcceptError::OutOfRange)
+        );
+        assert_eq!(
+            vec.accept(ActionTarget::This, trigger("rm", &[("idx", 2)])),
+            Err(AcceptError::OutOfRange)
+        );
+        assert_eq!(
+            vec.accept(ActionTarget::This, trigger("mv", &[("from", 0), ("to", 2)])),
+            Err(AcceptError::OutOfRange)
+        );
+        assert_eq!(
+            vec.accept(ActionTarget::This, trigger("rm", &[])),
+            Err(AcceptError::WrongType {
+                expected: "an index"
+            })
+        );
+        assert_eq!(
+            vec.accept(ActionTarget::This, trigger("bogus", &[])),
+            Err(AcceptError::WrongType {
+                expected: "a trigger"
+            })
+        );
+        assert_eq!(
+            vec.accept(ActionTarget::This, Parameter::UnsignedInt(0)),
+            Err(AcceptError::WrongType {
+                expected: "a trigger"
+            })
+        );
+        assert_eq!(vec, [1, 2]);
+    }
+
+    #[test]
+    fn accept_errors_propagate_from_elements() {
+        let mut vec = vec![[1u8, 2]];
+        assert_eq!(
+            vec.accept(
+                ActionTarget::Field(&["0", "1"]),
+                Parameter::UnsignedInt(300)
+            ),
+            Err(AcceptError::OutOfRange)
+        );
+        assert_eq!(
+            vec.accept(ActionTarget::Field(&["0", "1"]), Parameter::Float(1.0)),
+            Err(AcceptError::WrongType {
+                expected: "an unsigned integer"
+            })
+        );
+        assert_eq!(
+            vec.accept(ActionTarget::Field(&["1", "0"]), Parameter::UnsignedInt(3)),
+            Err(AcceptError::UnknownField("1".to_owned()))
+        );
+        assert_eq!(
+            vec.accept(ActionTarget::Field(&["0"]), Parameter::UnsignedInt(3)),
+            Err(AcceptError::MissingField)
+        );
+        assert_eq!(
+            vec.accept(
+                ActionTarget::Field(&["0", "1", "x"]),
+                Parameter::UnsignedInt(3)
+            ),
+            Err(AcceptError::UnknownField("x".to_owned()))
+        );
+        assert_eq!(
+            vec.accept(ActionTarget::Field(&["0", "1"]), Parameter::UnsignedInt(3)),
+            Ok(false)
+        );
+        assert_eq!(vec, [[1, 3]]);
+    }
+
+    #[test]
+    fn unknown_fields_have_empty_values() {
+        let vec = vec![1u32];
+        assert_eq!(
+            vec.get_self(ActionTarget::Field(&["5"])).serialize(),
+            "nlivemod:struct{}"
+        );
+        assert_eq!(
+            vec.repr_default(ActionTarget::Field(&["x"])).name,
+            ["livemod", "struct"]
+        );
+        let range = 0u32..1;
+        assert_eq!(
+            range.get_self(ActionTarget::Field(&["middle"])).serialize(),
+            "nlivemod:struct{}"
+        );
+    }
+
+    #[test]
+    fn range_bounds_drag_each_other() {
+        let mut range = 2u32..5;
+        assert_eq!(
+            range.accept(ActionTarget::Field(&["start"]), Parameter::UnsignedInt(3)),
+            Ok(false)
+        );
+        assert_eq!(range, 3..5);
+        assert_eq!(
+            range.accept(ActionTarget::Field(&["start"]), Parameter::UnsignedInt(8)),
+            Ok(true)
+        );
+        assert_eq!(range, 8..8);
+        let mut range = 2u32..=5;
+        assert_eq!(
+            range.accept(ActionTarget::Field(&["end"]), Parameter::UnsignedInt(1)),
+            Ok(true)
+        );
+        assert_eq!(range, 1..=1);
+        assert_eq!(
+            range.accept(ActionTarget::Field(&["step"]), Parameter::UnsignedInt(1)),
+            Err(AcceptError::UnknownField("step".to_owned()))
+        );
+    }
+
+    #[test]
+    fn wrapping_angles_stay_in_range() {
+        let wrap = Angle.wrapping(0.0..std::f64::consts::TAU);
+        assert_eq!(wrap.wrap(-1e-18), 0.0);
+        assert!((wrap.wrap(7.0) - (7.0 - std::f64::consts::TAU)).abs() < 1e-12);
+        let mut angle = -1e-9f32;
+        assert!(LiveModRepr::<f32>::correct(&wrap, &mut angle));
+        assert!((angle as f64) < std::f64::consts::TAU);
+        let mut angle = 1.0f32;
+        assert!(!LiveModRepr::<f32>::correct(&wrap, &mut angle));
+    }
+
+    /// An enum-like value which only allows switching to some of its variants.
+    #[derive(Clone, Debug, PartialEq)]
+    struct Mode {
+        variant: String,
+        allowed: Vec<&'static str>,
+        count: u32,
+    }
+
+    impl LiveMod for Mode {
+        fn repr_default(&self, _target: ActionTarget) -> Namespaced<Repr> {
+            Namespaced::unknown_field_repr()
+        }
+
+        fn accept(
+            &mut self,
+            target: ActionTarget,
+            value: Parameter<Value>,
+        ) -> Result<bool, AcceptError> {
+            match target.strip_one_field() {
+                Some(("variant", _)) => {
+                    let variant = value
+                        .try_into_string()
+                        .map_err(|_| AcceptError::WrongType {
+                            expected: "a variant",
+                        })?;
+                    if !self.allowed.contains(&variant.as_str()) {
+                        return Err(AcceptError::UnknownVariant(variant));
+                    }
+                    self.variant = variant;
+                    Ok(true)
+                }
+                Some(("count", field_target)) => self.count.accept(field_target, value),
+                Some((field, _)) => Err(AcceptError::UnknownField(field.to_owned())),
+                None => Err(AcceptError::MissingField),
+            }
+        }
+
+        fn get_self(&self, _target: ActionTarget) -> Parameter<Value> {
+            Parameter::Namespaced(Namespaced::basic_structure_value(&[(
+                "variant".to_owned(),
+                Parameter::String(self.variant.clone()),
+            )]))
+        }
+    }
+
+    fn mode(variant: &str, allowed: &[&'static str]) -> Mode {
+        Mode {
+            variant: variant.to_owned(),
+            allowed: allowed.to_vec(),
+            count: 0,
+        }
+    }
+
+    #[test]
+    fn stashed_restores_variants() {
+        let mut stashed = Stashed::new(mode("a", &["a", "b"]));
+        let variant = ActionTarget::Field(&["variant"]);
+        stashed
+            .accept(ActionTarget::Field(&["count"]), Parameter::UnsignedInt(5))
+            .unwrap();
+        assert_eq!(
+            stashed.accept(variant, Parameter::String("b".to_owned())),
+            Ok(true)
+        );
+        assert_eq!(stashed.variant, "b");
+        assert_eq!(stashed.count, 5);
+        stashed.count = 9;
+        assert_eq!(
+            stashed.accept(variant, Parameter::String("a".to_owned())),
+            Ok(true)
+        );
+        assert_eq!(
+            *stashed,
+            Mode {
+                count: 5,
+                ..mode("a", &["a", "b"])
+            }
+        );
+        assert_eq!(
+            stashed.accept(variant, Parameter::String("b".to_owned())),
+            Ok(true)
+        );
+        assert_eq!(stashed.count, 9);
+    }
+
+    #[test]
+    fn stashed_keeps_current_variant_on_error() {
+        let mut stashed = Stashed::new(mode("a", &["b"]));
+        let variant = ActionTarget::Field(&["variant"]);
+        assert_eq!(
+            stashed.accept(variant, Parameter::String("b".to_owned())),
+            Ok(true)
+        );
+        // The stashed value of `a` can't switch back to itself
+        assert_eq!(
+            stashed.accept(variant, Parameter::String("a".to_owned())),
+            Err(AcceptError::UnknownVariant("a".to_owned()))
+        );
+        assert_eq!(stashed.variant, "b");
+        assert!(stashed.stash.contains_key("a"));
+        assert_eq!(
+            stashed.accept(variant, Parameter::String("c".to_owned())),
+            Err(AcceptError::UnknownVariant("c".to_owned()))
+        );
+        assert_eq!(stashed.variant, "b");
+    }
+}
@@ -0,0 +1,231 @@
+//! `LiveMod` implementations for [`nalgebra`] types, enabled by the `nalgebra` feature.
+
+use nalgebra::{Point, SMatrix, Scalar, UnitQuaternion};
+
+use crate::{
+    AcceptError, ActionTarget, BuiltinRepr, LiveMod, LiveModCtor, Namespaced, Parameter, Repr,
+    Value,
+};
+
+const COMPONENT_NAMES: [&str; 6] = ["x", "y", "z", "w", "a", "b"];
+
+fn component_name(index: usize, len: usize) -> String {
+    if len <= COMPONENT_NAMES.len() {
+        COMPONENT_NAMES[index].to_owned()
+    } else {
+        index.to_string()
+    }
+}
+
+fn component_index(name: &str, len: usize) -> Option<usize> {
+    if len <= COMPONENT_NAMES.len() {
+        COMPONENT_NAMES[..len]
+            .iter()
+            .position(|component| *component == name)
+    } else {
+        name.parse().ok().filter(|index| *index < len)
+    }
+}
+
+/// Get the index of a named component, or an [`AcceptError::UnknownField`] if there is no such component.
+fn accept_index(name: &str, len: usize) -> Result<usize, AcceptError> {
+    component_index(name, len).ok_or_else(|| AcceptError::UnknownField(name.to_owned()))
+}
+
+fn vector_repr<T: LiveModCtor>(len: usize) -> Namespaced<Repr> {
+    BuiltinRepr::Vector {
+        components: (0..len)
+            .map(|i| (component_name(i, len), T::repr_static()))
+            .collect(),
+    }
+    .into()
+}
+
+fn vector_value<'a, T: LiveMod + 'a>(
+    components: impl ExactSizeIterator<Item = &'a T>,
+) -> Parameter<Value> {
+    let len = components.len();
+    Parameter::Namespaced(Namespaced::new(
+        vec!["livemod".to_owned(), "vector".to_owned()],
+        components
+            .enumerate()
+            .map(|(i, v)| (component_name(i, len), v.get_self(ActionTarget::This)))
+            .collect(),
+    ))
+}
+
+/// Column vectors are represented as a `livemod:vector`, and other matrices as a structure of their columns, in the
+/// same way as `glam` matrices.
+impl<T, const R: usize, const C: usize> LiveMod for SMatrix<T, R, C>
+where
+    T: Scalar + LiveModCtor,
+{
+    fn repr_default(&self, target: ActionTarget) -> Namespaced<Repr> {
+        if let Some((_, field_target)) = target.strip_one_field() {
+            if C == 1 || field_target.strip_one_field().is_some() {
+                T::repr_static()
+            } else {
+                vector_repr::<T>(R)
+            }
+        } else if C == 1 {
+            vector_repr::<T>(R)
+        } else {
+            Namespaced::basic_structure_repr(
+                &format!("Matrix{}x{}", R, C),
+                &(0..C)
+                    .map(|column| (column.to_string(), vector_repr::<T>(R)))
+                    .collect::<Vec<_>>(),
+            )
+        }
+    }
+
+    fn accept(
+        &mut self,
+        target: ActionTarget,
+        value: Parameter<Value>,
+    ) -> Result<bool, AcceptError> {
+        if let Some((field, field_target)) = target.strip_one_field() {
+            if C == 1 {
+                self[(accept_index(field, R)?, 0)].accept(field_target, value)
+            } else if let Some((row, row_target)) = field_target.strip_one_field() {
+                let column = field
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|column| *column < C)
+                    .ok_or_else(|| AcceptError::UnknownField(field.to_owned()))?;
+                self[(accept_index(row, R)?, column)].accept(row_target, value)
+            } else {
+                Err(AcceptError::MissingField)
+            }
+        } else {
+            Err(AcceptError::MissingField)
+        }
+    }
+
+    fn get_self(&self, target: ActionTarget) -> Parameter<Value> {
+        if let Some((field, field_target)) = target.strip_one_field() {
+            if C == 1 {
+                match component_index(field, R) {
+                    Some(row) => self[(row, 0)].get_self(field_target),
+                    None => Namespaced::unknown_field_value(),
+                }
+            } else {
+                let column = match field.parse::<usize>().ok().filter(|column| *column < C) {
+                    Some(column) => column,
+                    None => return Namespaced::unknown_field_value(),
+                };
+                if let Some((row, row_target)) = field_target.strip_one_field() {
+                    match component_index(row, R) {
+                        Some(row) => self[(row, column)].get_self(row_target),
+                        None => Namespaced::unknown_field_value(),
+                    }
+                } else {
+                    vector_value((0..R).map(|row| &self[(row, column)]))
+                }
+            }
+        } else if C == 1 {
+            vector_value(self.iter())
+        } else {
+            Parameter::Namespaced(Namespaced::basic_structure_value(
+                &(0..C)
+                    .map(|column| {
+                        (
+                            column.to_string(),
+                            vector_value((0..R).map(|row| &self[(row, column)])),
+                        )
+                    })
+                    .collect::<Vec<_>>(),
+            ))
+        }
+    }
+}
+
+impl<T, const D: usize> LiveMod for Point<T, D>
+where
+    T: Scalar + LiveModCtor,
+{
+    fn repr_default(&self, target: ActionTarget) -> Namespaced<Repr> {
+        self.coords.repr_default(target)
+    }
+
+    fn accept(
+        &mut self,
+        target: ActionTarget,
+        value: Parameter<Value>,
+    ) -> Result<bool, AcceptError> {
+        self.coords.accept(target, value)
+    }
+
+    fn get_self(&self, target: ActionTarget) -> Parameter<Value> {
+        self.coords.get_self(target)
+    }
+}
+
+/// Quaternions are represented by their euler angles, in the order yaw, pitch, roll.
+macro_rules! impl_unit_quaternion {
+    ($($t:ty),*) => {
+        $(
+        impl LiveMod for UnitQuaternion<$t> {
+            fn repr_default(&self, target: ActionTarget) -> Namespaced<Repr> {
+                if target.strip_one_field().is_some() {
+                    BuiltinRepr::Angle { wrap: None }.into()
+                } else {
+                    BuiltinRepr::Vector {
+                        components: ["yaw", "pitch", "roll"]
+                            .iter()
+                            .map(|component| {
+                                (
+                                    component.to_string(),
+                                    BuiltinRepr::Angle { wrap: None }.into(),
+                                )
+                            })
+                            .collect(),
+                    }
+                    .into()
+                }
+            }
+
+            fn accept(&mut self, target: ActionTarget, value: Parameter<Value>) -> Result<bool, AcceptError> {
+                if let Some((field, field_target)) = target.strip_one_field() {
+                    let (mut roll, mut pitch, mut yaw) = self.euler_angles();
+                    match field {
+                        "yaw" => yaw.accept(field_target, value)?,
+                        "pitch" => pitch.accept(field_target, value)?,
+                        "roll" => roll.accept(field_target, value)?,
+                        _ => return Err(AcceptError::UnknownField(field.to_owned())),
+                    };
+                    *self = UnitQuaternion::from_euler_angles(roll, pitch, yaw);
+                    // The rotation may be decomposed into different angles, which the viewer needs to be sent
+                    Ok(true)
+                } else {
+                    Err(AcceptError::MissingField)
+                }
+            }
+
+            fn get_self(&self, target: ActionTarget) -> Parameter<Value> {
+                let (roll, pitch, yaw) = self.euler_angles();
+                if let Some((field, field_target)) = target.strip_one_field() {
+                    match field {
+                        "yaw" => yaw.get_self(field_target),
+                        "pitch" => pitch.get_self(field_target),
+                        "roll" => roll.get_self(field_target),
+                        _ => Namespaced::unknown_field_value(),
+                    }
+                } else {
+                    Parameter::Namespaced(Namespaced::new(
+                        vec!["livemod".to_owned(), "vector".to_owned()],
+                        IntoIterator::into_iter([
+                            ("yaw".to_owned(), yaw.get_self(ActionTarget::This)),
+                            ("pitch".to_owned(), pitch.get_self(ActionTarget::This)),
+                            ("roll".to_owned(), roll.get_self(ActionTarget::This)),
+                        ])
+                        .collect(),
+                    ))
+                }
+            }
+        }
+        )*
+    };
+}
+
+impl_unit_quaternion!(f32, f64);
@@ -1,11 +1,13 @@
 use std::{
     collections::HashMap,
+    convert::TryFrom,
+    ops::{Range, RangeInclusive},
     sync::atomic::{AtomicBool, Ordering},
 };
 
 use livemod::{
-    livemod_static, ActionTarget, LiveMod, LiveModHandle, Multiline, Namespaced, Parameter, Repr,
-    Slider, TriggerFn, Value,
+    livemod_static, AcceptError, ActionTarget, Angle, Color, LiveMod, LiveModCtor, LiveModHandle,
+    MapWith, Multiline, Namespaced, Parameter, Repr, Slider, Stashed, TriggerFn, Value, VecWith,
 };
 
 livemod_static! {
@@ -33,9 +35,48 @@ fn main() {
             float_slider: 5.3,
         },
     );
+    let _stashed_enum =
+        livemod.create_variable("Stashed enum", Stashed::new(DerivedEnum::UnitVariant));
+    let _generic = livemod.create_variable(
+        "Generic",
+        Spring {
+            stiffness: 10.0_f32,
+            damping: 0.5,
+            rest_length: 1_u32,
+        },
+    );
+    let _generic_enum = livemod.create_variable("Generic enum", Generic::Some(3_i32));
+    let mut borrowed_value = 5_u32;
+    let _borrowed = unsafe {
+        // SAFETY: `borrowed_value` is not used until `_borrowed` is dropped.
+        livemod.create_variable_unchecked(
+            "Borrowed",
+            Borrowed {
+                value: &mut borrowed_value,
+            },
+        )
+    };
     let mut can_remove = Some(livemod.create_variable("Remove me", false));
     let _vector = livemod.create_variable("Vector", vec![6.4, 8.2]);
     let _map = livemod.create_variable("Map", HashMap::<String, u32>::new());
+    let _id_map = livemod.create_variable("Entities", HashMap::<EntityId, f32>::new());
+    let _team_map = livemod.create_variable("Teams", HashMap::<Team, u32>::new());
+    let _springs = livemod.create_variable(
+        "Springs",
+        VecWith::new(Vec::new(), || Spring {
+            stiffness: 10.0_f32,
+            damping: 0.5_f32,
+            rest_length: 1.0_f32,
+        }),
+    );
+    let _team_springs = livemod.create_variable(
+        "Team springs",
+        MapWith::new(HashMap::<Team, Spring<f32, u32>>::new(), || Spring {
+            stiffness: 10.0,
+            damping: 0.5,
+            rest_length: 1,
+        }),
+    );
     let _trigger = unsafe {
         // SAFETY: `running` is dropped after `livemod`.
         livemod.create_variable_unchecked(
@@ -68,6 +109,7 @@ fn main() {
         }
         if *cur_derived != prev_derived {
             println!("Derived: {:?}", *cur_derived);
+            cur_derived.updates_received += 1;
             prev_derived = cur_derived.clone();
             #[allow(clippy::float_cmp)]
             if cur_derived.floating_point != 3.2 {
@@ -106,10 +148,19 @@ impl LiveMod for Data {
         .into()
     }
 
-    fn accept(&mut self, target: ActionTarget, value: Parameter<Value>) -> bool {
-        debug_assert!(target.is_this());
-        self.value = value.try_into_unsigned_int().unwrap() as u32;
-        false
+    fn accept(
+        &mut self,
+        target: ActionTarget,
+        value: Parameter<Value>,
+    ) -> Result<bool, AcceptError> {
+        target.expect_this()?;
+        let value = value
+            .try_into_unsigned_int()
+            .map_err(|_| AcceptError::WrongType {
+                expected: "an unsigned integer",
+            })?;
+        self.value = u32::try_from(value).map_err(|_| AcceptError::OutOfRange)?;
+        Ok(false)
     }
 
     fn get_self(&self, target: ActionTarget) -> Parameter<Value> {
@@ -120,6 +171,9 @@ impl LiveMod for Data {
 
 #[derive(Debug, LiveMod, PartialEq, Clone)]
 struct DerivedData {
+    /// The first value.
+    ///
+    /// Shown as a slider from 0 to 500.
     #[livemod(repr = Slider(0..=500))]
     value_1: u32,
     #[livemod(rename = "signed value")]
@@ -127,12 +181,60 @@ struct DerivedData {
     floating_point: f32,
     #[livemod(repr = Slider(-5.0..=10.0))]
     double_float: f64,
+    #[livemod(repr = Slider(0.0001..=1.0).logarithmic().precision(4))]
+    learning_rate: f64,
+    #[livemod(repr = Slider(0..=1000).step(10).unit("ms"))]
+    #[livemod(tooltip = "Time to wait between updates")]
+    delay: u32,
     #[livemod(skip)]
     runtime_flag: bool,
     toggleable_flag: bool,
     singleline_string: String,
     #[livemod(repr = Multiline)]
     multiline_string: String,
+    #[livemod(group = "Spawning")]
+    spawn_count: RangeInclusive<u32>,
+    #[livemod(repr = Slider(0.0..=10.0), group = "Spawning")]
+    spawn_interval: Range<f32>,
+    #[livemod(repr = Color, group = "Appearance")]
+    tint: [f32; 4],
+    #[livemod(repr = Color, group = "Appearance")]
+    packed_tint: u32,
+    #[livemod(repr = Angle)]
+    rotation: f32,
+    #[livemod(repr = Angle.wrapping(-std::f64::consts::PI..std::f64::consts::PI))]
+    heading: f64,
+    quality: Quality,
+    marker: Marker,
+    #[livemod(min = 0, max = 10, step = 0.5, unit = "m/s")]
+    speed: f32,
+    #[livemod(min = 1)]
+    lives: u8,
+    #[livemod(flatten)]
+    physics: Physics,
+    #[livemod(min = 0.1, on_change = DerivedData::update_inverse_scale)]
+    scale: f32,
+    #[livemod(skip)]
+    inverse_scale: f32,
+    #[livemod(validate = round_to_even)]
+    even_number: u32,
+    #[livemod(readonly)]
+    updates_received: u32,
+    #[livemod(visible_if = "toggleable_flag")]
+    flag_message: String,
+    #[livemod(enabled_if = "quality != Low")]
+    shadow_distance: f32,
+}
+
+impl DerivedData {
+    fn update_inverse_scale(&mut self) {
+        self.inverse_scale = 1.0 / self.scale;
+    }
+}
+
+fn round_to_even(value: &mut u32) -> bool {
+    *value -= *value % 2;
+    true
 }
 
 impl Default for DerivedData {
@@ -142,21 +244,60 @@ impl Default for DerivedData {
             value_2: 2,
             floating_point: 3.2,
             double_float: 6.4,
+            learning_rate: 0.01,
+            delay: 250,
             runtime_flag: false,
             toggleable_flag: true,
             singleline_string: "One line".to_owned(),
             multiline_string: "Multiple\nlines".to_owned(),
+            spawn_count: 1..=4,
+            spawn_interval: 0.5..2.0,
+            tint: [1.0, 0.5, 0.0, 1.0],
+            packed_tint: 0x3080FFFF,
+            rotation: std::f32::consts::FRAC_PI_4,
+            heading: 0.0,
+            quality: Quality::Medium,
+            marker: Marker,
+            speed: 2.5,
+            lives: 3,
+            physics: Physics {
+                gravity: 9.8,
+                friction: 0.3,
+            },
+            scale: 2.0,
+            inverse_scale: 0.5,
+            even_number: 4,
+            updates_received: 0,
+            flag_message: "The flag is set".to_owned(),
+            shadow_distance: 50.0,
         }
     }
 }
 
+#[derive(Clone, Debug, PartialEq, LiveMod)]
+#[livemod(on_change = Physics::keep_gravity_downwards)]
+struct Physics {
+    gravity: f32,
+    #[livemod(min = 0.0, max = 1.0)]
+    friction: f32,
+}
+
+impl Physics {
+    fn keep_gravity_downwards(&mut self) {
+        self.gravity = self.gravity.abs();
+    }
+}
+
 #[derive(Default, LiveMod)]
+#[livemod(label = "Pair of integers")]
 struct DerivedTuple(u32, u64);
 
 #[derive(Clone, Debug, PartialEq, LiveMod)]
 #[allow(clippy::enum_variant_names)]
+#[livemod(rename_all = "Title Case")]
 enum DerivedEnum {
     UnitVariant,
+    #[livemod(default = DerivedEnum::TupleVariant(1.5, "Hello".to_owned()))]
     TupleVariant(f32, #[livemod(repr = Multiline)] String),
     StructVariant {
         #[livemod(default = 42)]
@@ -165,3 +306,44 @@ enum DerivedEnum {
         float_slider: f32,
     },
 }
+
+#[derive(LiveMod)]
+struct Spring<T, U> {
+    stiffness: T,
+    damping: T,
+    rest_length: U,
+}
+
+#[derive(LiveMod)]
+struct Borrowed<'a> {
+    value: &'a mut u32,
+}
+
+#[derive(Clone, Debug, PartialEq, LiveMod)]
+enum Generic<T> {
+    None,
+    Some(T),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, LiveMod)]
+enum Quality {
+    Low,
+    Medium,
+    High,
+    #[livemod(rename = "Ultra (slow)")]
+    Ultra,
+}
+
+#[derive(Clone, Debug, PartialEq, LiveMod)]
+struct Marker;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, LiveMod, LiveModCtor)]
+struct EntityId(u32);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, LiveMod, LiveModCtor)]
+enum Team {
+    Red,
+    Blue,
+    #[livemod(rename = "No team")]
+    Unassigned,
+}